@@ -0,0 +1,868 @@
+use std::collections::HashMap;
+
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{
+    ext::IdentExt, parse::ParseStream, parse_macro_input, punctuated::Punctuated, spanned::Spanned,
+    Attribute, Data, DeriveInput, Expr, Fields, GenericArgument, Ident, LitInt, PathArguments,
+    Token, Type,
+};
+
+/// Derives `Bind` for a struct of resources and generates its `BindLayout`
+///
+/// The struct must have a `bind_group: wgpu::BindGroup` field and a `layout`
+/// field, whose type names the layout struct that is generated alongside it.
+/// Every other field is a resource marked with one of the attributes below,
+/// in any order, taking a binding index, the shader stages it is visible to
+/// and then any options:
+///
+/// - `#[uniform(0, VERTEX)]` on a `UniformBuffer<T>`
+/// - `#[dynamic_uniform(0, VERTEX)]` on a `DynamicUniformBuffer<T>`
+/// - `#[storage(0, COMPUTE, read_only = true, dynamic = false)]` on a `StorageBuffer<T>`
+/// - `#[dynamic_buffer(0, COMPUTE, read_only = false, dynamic = true)]` on a `DynamicBuffer<T>`
+/// - `#[texture(0, FRAGMENT, sample_type = .., multisampled = false, count = 1)]` on a `RawTexture<D>`
+/// - `#[storage_texture(0, COMPUTE, format = .., access = .., count = 1)]` on a `RawTexture<D>`
+/// - `#[sampler(0, FRAGMENT, binding_type = .., count = 1)]` on a `Sampler`
+///
+/// Stages are combined with `|`, as in `#[uniform(0, VERTEX | FRAGMENT)]`.
+/// The options take the same defaults as in `bind!`, and two resources
+/// sharing a binding index is a compile error. As with `bind!`, every resource
+/// gets a `set_<field>` that replaces it and rebuilds the bind group, a
+/// `<field>_mut` to change its contents and a `<FIELD>_LABEL` constant of
+/// `"<Bind>.<field>"` to create it with, and `update` replaces several
+/// resources with a single rebuild.
+///
+/// An optional `dirty: bool` field enables deferred rebuilds as in `bind!`,
+/// with a `replace_<field>` for every resource, `mark_dirty`, `is_dirty` and
+/// `refresh_if_dirty`
+///
+/// ```ignore
+/// #[derive(Bind)]
+/// pub struct MaterialBind {
+///     bind_group: wgpu::BindGroup,
+///     layout: MaterialBindLayout,
+///     #[texture(1, FRAGMENT, sample_type = wgpu::TextureSampleType::Float { filterable: true })]
+///     pub diffuse: RawTexture<Texture2D>,
+///     #[uniform(0, VERTEX | FRAGMENT)]
+///     pub tint: UniformBuffer<[f32; 4]>,
+///     #[sampler(2, FRAGMENT)]
+///     pub sampler: Sampler,
+/// }
+/// ```
+#[proc_macro_derive(
+    Bind,
+    attributes(
+        uniform,
+        dynamic_uniform,
+        storage,
+        dynamic_buffer,
+        texture,
+        storage_texture,
+        sampler
+    )
+)]
+pub fn derive_bind(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Kind {
+    Uniform,
+    DynamicUniform,
+    Storage,
+    DynamicBuffer,
+    Texture,
+    StorageTexture,
+    Sampler,
+}
+
+impl Kind {
+    const ALL: [(&'static str, Kind); 7] = [
+        ("uniform", Kind::Uniform),
+        ("dynamic_uniform", Kind::DynamicUniform),
+        ("storage", Kind::Storage),
+        ("dynamic_buffer", Kind::DynamicBuffer),
+        ("texture", Kind::Texture),
+        ("storage_texture", Kind::StorageTexture),
+        ("sampler", Kind::Sampler),
+    ];
+
+    fn of(attr: &Attribute) -> Option<Kind> {
+        Self::ALL
+            .iter()
+            .find(|(name, _)| attr.path().is_ident(name))
+            .map(|&(_, kind)| kind)
+    }
+
+    fn options(self) -> &'static [&'static str] {
+        match self {
+            Kind::Uniform | Kind::DynamicUniform => &[],
+            Kind::Storage | Kind::DynamicBuffer => &["read_only", "dynamic"],
+            Kind::Texture => &["sample_type", "multisampled", "count"],
+            Kind::StorageTexture => &["format", "access", "count"],
+            Kind::Sampler => &["binding_type", "count"],
+        }
+    }
+
+    fn required(self) -> &'static [&'static str] {
+        match self {
+            Kind::Texture => &["sample_type"],
+            Kind::StorageTexture => &["format", "access"],
+            _ => &[],
+        }
+    }
+}
+
+struct Resource {
+    field: Ident,
+    ty: Type,
+    kind: Kind,
+    binding: u32,
+    binding_span: Span,
+    visibility: Vec<Ident>,
+    options: HashMap<String, Expr>,
+}
+
+impl Resource {
+    fn parse(field: Ident, ty: Type, kind: Kind, attr: &Attribute) -> syn::Result<Self> {
+        attr.parse_args_with(|input: ParseStream| {
+            let binding: LitInt = input.parse()?;
+            input.parse::<Token![,]>()?;
+
+            let visibility = Punctuated::<Ident, Token![|]>::parse_separated_nonempty(input)?
+                .into_iter()
+                .collect();
+
+            let mut options = HashMap::new();
+
+            while !input.is_empty() {
+                input.parse::<Token![,]>()?;
+
+                if input.is_empty() {
+                    break;
+                }
+
+                let key: Ident = input.parse()?;
+                input.parse::<Token![=]>()?;
+                let value: Expr = input.parse()?;
+
+                let name = key.to_string();
+
+                if !kind.options().contains(&name.as_str()) {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        format!(
+                            "unknown option `{name}`, expected one of {:?}",
+                            kind.options()
+                        ),
+                    ));
+                }
+
+                if options.insert(name, value).is_some() {
+                    return Err(syn::Error::new(key.span(), "option given more than once"));
+                }
+            }
+
+            for required in kind.required() {
+                if !options.contains_key(*required) {
+                    return Err(syn::Error::new(
+                        attr.span(),
+                        format!("missing required option `{required}`"),
+                    ));
+                }
+            }
+
+            Ok(Self {
+                field,
+                ty,
+                kind,
+                binding: binding.base10_parse()?,
+                binding_span: binding.span(),
+                visibility,
+                options,
+            })
+        })
+    }
+
+    fn option(&self, name: &str, default: TokenStream) -> TokenStream {
+        match self.options.get(name) {
+            Some(value) => quote!(#value),
+            None => default,
+        }
+    }
+
+    fn count(&self) -> TokenStream {
+        match self.options.get("count") {
+            Some(count) => quote!(Some(std::num::NonZero::new(#count).unwrap())),
+            None => quote!(None),
+        }
+    }
+
+    /// The `T` of `UniformBuffer<T>`, `RawTexture<D>` and so on
+    fn inner_type(&self) -> syn::Result<&Type> {
+        let error = || {
+            syn::Error::new(
+                self.ty.span(),
+                "expected a resource type with a generic parameter",
+            )
+        };
+
+        let Type::Path(path) = &self.ty else {
+            return Err(error());
+        };
+
+        let segment = path.path.segments.last().ok_or_else(error)?;
+
+        let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+            return Err(error());
+        };
+
+        arguments
+            .args
+            .iter()
+            .find_map(|argument| match argument {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .ok_or_else(error)
+    }
+
+    fn layout_entry(&self) -> syn::Result<TokenStream> {
+        let binding = self.binding;
+        let visibility = self
+            .visibility
+            .iter()
+            .map(|stage| quote!(wgpu::ShaderStages::#stage));
+
+        let ty = match self.kind {
+            Kind::Uniform => quote! {
+                wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                }
+            },
+            Kind::DynamicUniform => {
+                let inner = self.inner_type()?;
+
+                quote! {
+                    wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: true,
+                        min_binding_size: std::num::NonZeroU64::new(
+                            std::mem::size_of::<#inner>() as u64
+                        ),
+                    }
+                }
+            }
+            Kind::Storage | Kind::DynamicBuffer => {
+                let dynamic_buffer = self.kind == Kind::DynamicBuffer;
+
+                let storage_buffer = !dynamic_buffer;
+
+                let read_only = self.option("read_only", quote!(#storage_buffer));
+                let dynamic = self.option("dynamic", quote!(#dynamic_buffer));
+
+                quote! {
+                    wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage {
+                            read_only: #read_only,
+                        },
+                        has_dynamic_offset: #dynamic,
+                        min_binding_size: None,
+                    }
+                }
+            }
+            Kind::Texture => {
+                let dimension = self.inner_type()?;
+                let sample_type = &self.options["sample_type"];
+                let multisampled = self.option("multisampled", quote!(false));
+
+                quote! {
+                    wgpu::BindingType::Texture {
+                        sample_type: #sample_type,
+                        view_dimension: <#dimension as TextureDimension>::VIEW_DIMENSION,
+                        multisampled: #multisampled,
+                    }
+                }
+            }
+            Kind::StorageTexture => {
+                let dimension = self.inner_type()?;
+                let format = &self.options["format"];
+                let access = &self.options["access"];
+
+                quote! {
+                    wgpu::BindingType::StorageTexture {
+                        access: #access,
+                        format: #format,
+                        view_dimension: <#dimension as TextureDimension>::VIEW_DIMENSION,
+                    }
+                }
+            }
+            Kind::Sampler => {
+                let binding_type =
+                    self.option("binding_type", quote!(wgpu::SamplerBindingType::Filtering));
+
+                quote!(wgpu::BindingType::Sampler(#binding_type))
+            }
+        };
+
+        let count = match self.kind {
+            Kind::Texture | Kind::StorageTexture | Kind::Sampler => self.count(),
+            _ => quote!(None),
+        };
+
+        Ok(quote_spanned! {self.binding_span=>
+            wgpu::BindGroupLayoutEntry {
+                binding: #binding,
+                visibility: #(#visibility)|*,
+                ty: #ty,
+                count: #count,
+            }
+        })
+    }
+
+    fn group_entry(&self, resource: TokenStream) -> TokenStream {
+        let binding = self.binding;
+
+        let resource = match self.kind {
+            Kind::Uniform | Kind::Storage | Kind::DynamicBuffer => {
+                quote!(unsafe { #resource.wgpu_buffer() }.as_entire_binding())
+            }
+            Kind::DynamicUniform => quote!(#resource.binding()),
+            Kind::Texture | Kind::StorageTexture => quote! {
+                wgpu::BindingResource::TextureView(unsafe { #resource.view() }.inner())
+            },
+            Kind::Sampler => quote! {
+                wgpu::BindingResource::Sampler(unsafe { #resource.inner() })
+            },
+        };
+
+        quote! {
+            wgpu::BindGroupEntry {
+                binding: #binding,
+                resource: #resource,
+            }
+        }
+    }
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let bind = &input.ident;
+    let vis = &input.vis;
+
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            "`Bind` cannot be derived for generic structs",
+        ));
+    }
+
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(
+            input.span(),
+            "`Bind` can only be derived for structs",
+        ));
+    };
+
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new(
+            data.fields.span(),
+            "`Bind` can only be derived for structs with named fields",
+        ));
+    };
+
+    let mut bind_layout = None;
+    let mut has_bind_group = false;
+    let mut has_dirty = false;
+    let mut resources = Vec::<Resource>::new();
+    let mut errors = Vec::<syn::Error>::new();
+
+    for field in &fields.named {
+        let ident = field.ident.clone().unwrap();
+
+        let mut attrs = field
+            .attrs
+            .iter()
+            .filter_map(|attr| Kind::of(attr).map(|kind| (kind, attr)));
+
+        let Some((kind, attr)) = attrs.next() else {
+            if ident == "bind_group" {
+                has_bind_group = true;
+            } else if ident == "dirty" {
+                has_dirty = true;
+            } else if ident == "layout" {
+                bind_layout = Some(&field.ty);
+            } else {
+                errors.push(syn::Error::new(
+                    ident.span(),
+                    "field is not a `bind_group`, `dirty` or `layout` and has no binding attribute",
+                ));
+            }
+
+            continue;
+        };
+
+        if ident == "bind_group" || ident == "dirty" || ident == "layout" {
+            errors.push(syn::Error::new(
+                attr.span(),
+                format!("`{ident}` is reserved and cannot be a resource"),
+            ));
+            continue;
+        }
+
+        for (_, extra) in attrs {
+            errors.push(syn::Error::new(
+                extra.span(),
+                "field already has a binding, each resource takes exactly one",
+            ));
+        }
+
+        match Resource::parse(ident, field.ty.clone(), kind, attr) {
+            Ok(resource) => resources.push(resource),
+            Err(error) => errors.push(error),
+        }
+    }
+
+    let mut bindings = HashMap::<u32, &Resource>::new();
+
+    for resource in &resources {
+        if let Some(first) = bindings.insert(resource.binding, resource) {
+            let mut error = syn::Error::new(
+                resource.binding_span,
+                format!(
+                    "binding {} is used by both `{}` and `{}`",
+                    resource.binding, first.field, resource.field
+                ),
+            );
+            error.combine(syn::Error::new(
+                first.binding_span,
+                format!("binding {} first used here", first.binding),
+            ));
+
+            errors.push(error);
+            bindings.insert(first.binding, first);
+        }
+    }
+
+    if !has_bind_group {
+        errors.push(syn::Error::new(
+            bind.span(),
+            "missing a `bind_group: wgpu::BindGroup` field",
+        ));
+    }
+
+    let bind_layout = match bind_layout {
+        Some(Type::Path(path)) if path.qself.is_none() && path.path.get_ident().is_some() => {
+            path.path.get_ident().unwrap()
+        }
+        Some(ty) => {
+            errors.push(syn::Error::new(
+                ty.span(),
+                "the type of `layout` must be a plain name for the layout to generate",
+            ));
+            return Err(combine(errors));
+        }
+        None => {
+            errors.push(syn::Error::new(
+                bind.span(),
+                "missing a `layout` field naming the layout to generate",
+            ));
+            return Err(combine(errors));
+        }
+    };
+
+    if !errors.is_empty() {
+        return Err(combine(errors));
+    }
+
+    let layout_entries = resources
+        .iter()
+        .map(Resource::layout_entry)
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let new_entries = resources.iter().map(|resource| {
+        let field = &resource.field;
+        resource.group_entry(quote!(#field))
+    });
+
+    let refresh_entries = resources.iter().map(|resource| {
+        let field = &resource.field;
+        resource.group_entry(quote!(self.#field))
+    });
+
+    let fields = resources
+        .iter()
+        .map(|resource| &resource.field)
+        .collect::<Vec<_>>();
+    let types = resources
+        .iter()
+        .map(|resource| &resource.ty)
+        .collect::<Vec<_>>();
+
+    let dynamic_buffers = resources
+        .iter()
+        .filter(|resource| resource.kind == Kind::DynamicBuffer)
+        .map(|resource| &resource.field)
+        .collect::<Vec<_>>();
+
+    let labels = resources.iter().map(|resource| {
+        let field = &resource.field;
+        let label = format_ident!("{}_LABEL", field.unraw().to_string().to_uppercase());
+
+        quote! {
+            pub const #label: &str = concat!(stringify!(#bind), ".", stringify!(#field));
+        }
+    });
+
+    let setters = resources.iter().map(|resource| {
+        let field = &resource.field;
+        let ty = &resource.ty;
+
+        let set = format_ident!("set_{}", field);
+        let field_mut = format_ident!("{}_mut", field);
+
+        let replace = has_dirty.then(|| {
+            let replace = format_ident!("replace_{}", field);
+
+            quote! {
+                /// Replaces the resource and returns the previous one, the bind
+                /// group is rebuilt by the next `refresh_if_dirty` so that several
+                /// resources can be replaced with a single rebuild
+                #[inline]
+                pub fn #replace(&mut self, #field: #ty) -> #ty {
+                    self.dirty = true;
+                    std::mem::replace(&mut self.#field, #field)
+                }
+            }
+        });
+
+        quote! {
+            /// Replaces the resource and rebuilds the bind group with it,
+            /// returning the previous one
+            #[inline]
+            pub fn #set(&mut self, render_context: &RenderContext, #field: #ty) -> #ty {
+                let previous = std::mem::replace(&mut self.#field, #field);
+                self.refresh(render_context);
+                previous
+            }
+
+            #replace
+
+            /// Borrows the resource to change its contents, such as to write
+            /// to a buffer, which does not need the bind group to be rebuilt
+            ///
+            /// If the resource itself is replaced, as when resizing a texture,
+            /// call `refresh` afterwards or change it through `update`
+            #[inline]
+            pub fn #field_mut(&mut self) -> &mut #ty {
+                &mut self.#field
+            }
+        }
+    });
+
+    let init_dirty = has_dirty.then(|| quote!(dirty: false,));
+    let clear_dirty = has_dirty.then(|| quote!(self.dirty = false;));
+
+    let dirty_tracking = has_dirty.then(|| {
+        quote! {
+            /// Marks the bind group to be rebuilt by the next
+            /// `refresh_if_dirty`, after a resource has been replaced in place
+            #[inline(always)]
+            pub const fn mark_dirty(&mut self) {
+                self.dirty = true;
+            }
+
+            #[inline(always)]
+            pub const fn is_dirty(&self) -> bool {
+                self.dirty
+            }
+
+            /// Rebuilds the bind group once if any resource has been replaced
+            /// or marked dirty, or any dynamic buffer reallocated, since it
+            /// was last built
+            #[inline]
+            pub fn refresh_if_dirty(&mut self, render_context: &RenderContext) -> bool {
+                let dirty = self.dirty #(| self.#dynamic_buffers.take_reallocated())*;
+
+                if dirty {
+                    self.refresh(render_context);
+                }
+
+                dirty
+            }
+        }
+    });
+
+    Ok(quote! {
+        #[allow(unused)]
+        #[repr(transparent)]
+        #[derive(Debug, Clone)]
+        #vis struct #bind_layout {
+            layout: wgpu::BindGroupLayout,
+        }
+
+        #[allow(unused)]
+        impl #bind_layout {
+            pub const LABEL: &str = stringify!(#bind_layout);
+
+            #[inline]
+            pub fn new(render_context: &RenderContext) -> Self {
+                let device = unsafe { render_context.device() };
+
+                Self {
+                    layout: device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                        entries: &<Self as BindLayoutEntries>::entries(),
+                        label: Some(Self::LABEL),
+                    }),
+                }
+            }
+
+            #[inline]
+            pub async fn new_checked(
+                render_context: &RenderContext,
+            ) -> Result<Self, ValidationError> {
+                render_context
+                    .capture_validation(|| Self::new(render_context))
+                    .await
+            }
+        }
+
+        impl BindLayoutEntries for #bind_layout {
+            fn entries() -> Vec<wgpu::BindGroupLayoutEntry> {
+                vec![#(#layout_entries,)*]
+            }
+        }
+
+        impl BindLayout for #bind_layout {
+            #[inline(always)]
+            fn wgpu_layout(&self) -> &wgpu::BindGroupLayout {
+                &self.layout
+            }
+        }
+
+        impl std::ops::Deref for #bind {
+            type Target = #bind_layout;
+
+            #[inline(always)]
+            fn deref(&self) -> &Self::Target {
+                &self.layout
+            }
+        }
+
+        #[allow(unused)]
+        impl #bind {
+            pub const LABEL: &str = stringify!(#bind);
+
+            // labels to create each resource with, as `<bind>.<field>`
+            #(#labels)*
+
+            #[inline]
+            pub fn new(
+                render_context: &RenderContext,
+                layout: #bind_layout,
+                #(#fields: #types,)*
+            ) -> Self {
+                let device = unsafe { render_context.device() };
+
+                let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    layout: layout.wgpu_layout(),
+                    entries: &[#(#new_entries,)*],
+                    label: Some(Self::LABEL),
+                });
+
+                Self {
+                    bind_group,
+                    layout,
+                    #init_dirty
+                    #(#fields,)*
+                }
+            }
+
+            #[inline]
+            pub async fn new_checked(
+                render_context: &RenderContext,
+                layout: #bind_layout,
+                #(#fields: #types,)*
+            ) -> Result<Self, ValidationError> {
+                render_context
+                    .capture_validation(|| Self::new(render_context, layout, #(#fields,)*))
+                    .await
+            }
+
+            #[inline]
+            pub fn refresh(&mut self, render_context: &RenderContext) {
+                let device = unsafe { render_context.device() };
+
+                self.bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    layout: self.layout.wgpu_layout(),
+                    entries: &[#(#refresh_entries,)*],
+                    label: Some(Self::LABEL),
+                });
+                #clear_dirty
+            }
+
+            /// Changes any number of resources through `update` and rebuilds
+            /// the bind group once afterwards
+            #[inline]
+            pub fn update<R>(
+                &mut self,
+                render_context: &RenderContext,
+                update: impl FnOnce(&mut Self) -> R,
+            ) -> R {
+                let result = update(self);
+                self.refresh(render_context);
+                result
+            }
+
+            #dirty_tracking
+
+            #(#setters)*
+
+            /// Refreshes the bind group if any of the dynamic buffers
+            /// have been reallocated since it was created
+            #[inline]
+            pub fn refresh_if_reallocated(&mut self, render_context: &RenderContext) -> bool {
+                let reallocated = false #(| self.#dynamic_buffers.take_reallocated())*;
+
+                if reallocated {
+                    self.refresh(render_context);
+                }
+
+                reallocated
+            }
+        }
+
+        impl Bind for #bind {
+            type Layout = #bind_layout;
+
+            #[inline(always)]
+            fn bind_group(&self) -> &wgpu::BindGroup {
+                &self.bind_group
+            }
+
+            #[inline]
+            fn layout(&self) -> &#bind_layout {
+                &self.layout
+            }
+        }
+    })
+}
+
+fn combine(errors: Vec<syn::Error>) -> syn::Error {
+    errors
+        .into_iter()
+        .reduce(|mut combined, error| {
+            combined.combine(error);
+            combined
+        })
+        .unwrap()
+}
+
+#[cfg(test)]
+mod derive_tests {
+    use super::*;
+
+    fn expand_str(input: TokenStream) -> Result<String, String> {
+        expand(syn::parse2(input).unwrap())
+            .map(|tokens| tokens.to_string())
+            .map_err(|error| error.to_string())
+    }
+
+    #[test]
+    fn test_derive_mixed_order() {
+        let expanded = expand_str(quote! {
+            struct MaterialBind {
+                bind_group: wgpu::BindGroup,
+                layout: MaterialBindLayout,
+                #[sampler(2, FRAGMENT)]
+                sampler: Sampler,
+                #[uniform(0, VERTEX | FRAGMENT)]
+                tint: UniformBuffer<[f32; 4]>,
+                #[dynamic_buffer(1, COMPUTE, read_only = true)]
+                lights: DynamicBuffer<f32>,
+            }
+        })
+        .unwrap();
+
+        assert!(expanded.contains("struct MaterialBindLayout"));
+        assert!(
+            expanded.contains("wgpu :: ShaderStages :: VERTEX | wgpu :: ShaderStages :: FRAGMENT")
+        );
+        assert!(expanded.contains("self . lights . take_reallocated ()"));
+        assert!(expanded.contains("pub fn set_tint"));
+        assert!(expanded.contains("pub fn sampler_mut"));
+        assert!(!expanded.contains("pub fn replace_tint"));
+        assert!(!expanded.contains("refresh_if_dirty"));
+    }
+
+    #[test]
+    fn test_derive_dirty_tracking() {
+        let expanded = expand_str(quote! {
+            struct MaterialBind {
+                bind_group: wgpu::BindGroup,
+                layout: MaterialBindLayout,
+                dirty: bool,
+                #[uniform(0, VERTEX)]
+                tint: UniformBuffer<[f32; 4]>,
+            }
+        })
+        .unwrap();
+
+        assert!(expanded.contains("dirty : false"));
+        assert!(expanded.contains("pub fn replace_tint"));
+        assert!(expanded.contains("pub fn refresh_if_dirty"));
+    }
+
+    #[test]
+    fn test_derive_duplicate_binding() {
+        let error = expand_str(quote! {
+            struct MaterialBind {
+                bind_group: wgpu::BindGroup,
+                layout: MaterialBindLayout,
+                #[uniform(0, VERTEX)]
+                tint: UniformBuffer<[f32; 4]>,
+                #[sampler(0, FRAGMENT)]
+                sampler: Sampler,
+            }
+        })
+        .unwrap_err();
+
+        assert!(error.contains("binding 0 is used by both `tint` and `sampler`"));
+    }
+
+    #[test]
+    fn test_derive_overlapping_field() {
+        let error = expand_str(quote! {
+            struct MaterialBind {
+                bind_group: wgpu::BindGroup,
+                layout: MaterialBindLayout,
+                #[uniform(0, VERTEX)]
+                #[uniform(1, FRAGMENT)]
+                tint: UniformBuffer<[f32; 4]>,
+            }
+        })
+        .unwrap_err();
+
+        assert!(error.contains("field already has a binding"));
+    }
+
+    #[test]
+    fn test_derive_missing_option() {
+        let error = expand_str(quote! {
+            struct MaterialBind {
+                bind_group: wgpu::BindGroup,
+                layout: MaterialBindLayout,
+                #[texture(0, FRAGMENT)]
+                diffuse: RawTexture<Texture2D>,
+            }
+        })
+        .unwrap_err();
+
+        assert!(error.contains("missing required option `sample_type`"));
+    }
+}
@@ -11,16 +11,24 @@ use r3_core::winit::{
 
 #[tokio::main]
 async fn main() {
-    let render_context = RenderContext::new(RenderContextConfig {
+    env_logger::init();
+
+    if let Err(error) = run().await {
+        eprintln!("{error}");
+        std::process::exit(1);
+    }
+}
+
+async fn run() -> Result<(), InitError> {
+    let render_context = RenderContext::try_new(RenderContextConfig {
         features: Some(
             wgpu::Features::all_webgpu_mask().difference(wgpu::Features::CLIP_DISTANCES),
         ),
         ..default()
     })
-    .await;
+    .await?;
 
-    env_logger::init();
-    let event_loop = EventLoop::new().unwrap();
+    let event_loop = EventLoop::new().map_err(InitError::EventLoop)?;
 
     let mut handler = Handler::new(
         render_context,
@@ -38,12 +46,15 @@ async fn main() {
             on_start,
             on_event,
             |_: &mut _, _: &_| {},
-            on_draw,
+            WithSurfaceEvent {
+                on_draw,
+                on_surface_event,
+            },
             |_: &mut _, _: &_, _| println!("Exiting..."),
         ),
     );
 
-    handler.run(event_loop);
+    handler.run(event_loop)
 }
 
 fn on_start(app: AppConfig<Void>, _: &ActiveEventLoop) -> State {
@@ -58,7 +69,7 @@ fn on_start(app: AppConfig<Void>, _: &ActiveEventLoop) -> State {
         CameraBind::new(
             app.render_context,
             CameraBindLayout::new(app.render_context),
-            UniformBuffer::new(app.render_context),
+            UniformBuffer::new(app.render_context, Some(CameraBind::UNIFORM_LABEL)),
         ),
         Projection::new(width as _, height as _),
         camera_controller.generate_transform(),
@@ -66,7 +77,7 @@ fn on_start(app: AppConfig<Void>, _: &ActiveEventLoop) -> State {
 
     let layout = NewLayout::new(app.render_context, camera.layout());
 
-    let mesh = SimpleMesh::new_uint16(app.render_context, VERTICES, INDICES);
+    let mesh = SimpleMesh::new_uint16(app.render_context, Some("Pentagon"), VERTICES, INDICES);
 
     let shader = NewShader::new(app.render_context, &layout);
 
@@ -95,7 +106,7 @@ fn on_event(app: &mut App<State>, _: &ActiveEventLoop, _: WindowId, event: Windo
             .resize(new_size.width as _, new_size.height as _);
         camera.update_projection();
 
-        post_proc.render.resize(
+        post_proc.render_mut().resize(
             &app.render_context,
             None,
             wgpu::Extent3d {
@@ -104,12 +115,14 @@ fn on_event(app: &mut App<State>, _: &ActiveEventLoop, _: WindowId, event: Windo
                 depth_or_array_layers: 1,
             },
         );
-        post_proc.refresh(&app.render_context);
+        // the texture is replaced by a new one, rebuilt before the next draw
+        post_proc.mark_dirty();
     };
 }
 
 fn on_draw(app: &mut App<State>, _: &ActiveEventLoop, _: WindowId) {
     let post_proc = &mut app.state.post_processing_layout.post_proc;
+    post_proc.refresh_if_dirty(&app.render_context);
 
     let camera = &mut app.state.camera;
     let camera_controller = &mut app.state.camera_controller;
@@ -120,7 +133,10 @@ fn on_draw(app: &mut App<State>, _: &ActiveEventLoop, _: WindowId) {
 
     camera.write_buffer(&app.render_context);
 
-    let mut encoder = app.window.command_encoder();
+    // a failure is reported to `on_surface_event`
+    let Ok(mut encoder) = app.window.try_command_encoder() else {
+        return;
+    };
 
     encoder
         .render_pass(None, true)
@@ -139,6 +155,12 @@ fn on_draw(app: &mut App<State>, _: &ActiveEventLoop, _: WindowId) {
     encoder.present();
 }
 
+fn on_surface_event(_: &mut App<State>, _: &ActiveEventLoop, _: WindowId, event: SurfaceEvent) {
+    if let SurfaceEvent::Skipped(error) = event {
+        eprintln!("Skipping frame: {error}");
+    }
+}
+
 #[derive(Debug)]
 struct State {
     camera_controller: GroundedCamera,
@@ -163,6 +185,7 @@ impl NewLayout {
             layout: RawLayout::new(
                 render_context,
                 LayoutConfig {
+                    label: Some("New Layout"),
                     bind_group_layouts: &[camera.wgpu_layout()],
                     ..default()
                 },
@@ -195,9 +218,20 @@ struct NewShader {
 impl NewShader {
     #[inline]
     fn new(render_context: &RenderContext, layout: &NewLayout) -> Self {
+        let mut composer = ShaderComposer::new();
+        composer.define("CAMERA_GROUP", 0);
+
+        let source = composer
+            .compose("shader.wgsl", include_str!("shader.wgsl"))
+            .unwrap_or_else(|error| panic!("{error}"));
+
+        ShaderReflection::from_wgsl(source.source())
+            .and_then(|reflection| reflection.validate(&[&CameraBindLayout::entries()]))
+            .unwrap_or_else(|error| panic!("{error}"));
+
         let module = render_context.create_shader_module(
             Some("Shader"),
-            wgpu::ShaderSource::Wgsl(include_str!("shader.wgsl").into()),
+            wgpu::ShaderSource::Wgsl(String::from(source).into()),
         );
 
         Self {
@@ -253,8 +287,10 @@ impl PostProcessingLayout {
         let layout = RawLayout::new(
             render_context,
             LayoutConfig {
+                label: Some("Post Processing Layout"),
                 bind_group_layouts: &[post_proc.wgpu_layout()],
                 format: wgpu::TextureFormat::Bgra8UnormSrgb,
+                ..default()
             },
         );
 
@@ -283,10 +319,14 @@ struct CrtShader {
 
 impl CrtShader {
     fn new(render_context: &RenderContext, layout: &PostProcessingLayout) -> Self {
-        let module = render_context.create_shader_module(
-            Some("Post Processing"),
-            wgpu::ShaderSource::Wgsl(include_str!("post_processing.wgsl").into()),
-        );
+        let module = ShaderComposer::new()
+            .create_shader_module(
+                render_context,
+                Some("Post Processing"),
+                "post_processing.wgsl",
+                include_str!("post_processing.wgsl"),
+            )
+            .unwrap_or_else(|error| panic!("{error}"));
 
         Self {
             pipeline: layout.create_pipeline(
@@ -1,23 +1,4 @@
-struct VertexOutput {
-    @location(0) uv: vec2<f32>,
-    @builtin(position) clip_position: vec4<f32>,
-};
-
-@vertex
-fn vs(
-    @builtin(vertex_index) vi: u32,
-) -> VertexOutput {
-    var out: VertexOutput;
-    // Triangle from (0,0) (2,0) (0,1)
-    out.uv = vec2<f32>(
-        f32((vi << 1u) & 2u),
-        f32(vi & 2u),
-    );
-    out.clip_position = vec4<f32>((out.uv * 2.0) - 1.0, 0.0, 1.0);
-    // Flip Y
-    out.uv.y = 1.0 - out.uv.y;
-    return out;
-}
+#include "r3/fullscreen"
 
 @group(0)
 @binding(0)
@@ -28,7 +9,7 @@ var pp_image: texture_2d<f32>;
 var pp_sampler: sampler;
 
 @fragment
-fn fs(vs: VertexOutput) -> @location(0) vec4<f32> {
+fn fs(vs: FullscreenVertex) -> @location(0) vec4<f32> {
     var uv = fish_eye(vs.uv);
 
     var colour = crt(uv);
@@ -8,12 +8,7 @@ struct VertexOutput {
     @location(0) color: vec3<f32>,
 };
 
-struct CameraUniform {
-    view_proj: mat4x4<f32>,
-};
-
-@group(0) @binding(0)
-var<uniform> camera: CameraUniform;
+#include "r3/camera"
 
 @vertex
 fn vs(
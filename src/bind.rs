@@ -1,6 +1,12 @@
 pub mod dynamic_buffer;
+pub mod dynamic_uniform_buffer;
+mod readback;
 pub mod storage_buffer;
+pub mod texture_registry;
 pub mod uniform_buffer;
+pub mod wgsl_layout;
+
+pub use r3_derive::Bind;
 
 pub trait Bind {
     type Layout: BindLayout;
@@ -13,6 +19,13 @@ pub trait BindLayout {
     fn wgpu_layout(&self) -> &wgpu::BindGroupLayout;
 }
 
+/// Entries a `BindLayout` is created from, implemented by the layouts of
+/// `bind!` and `#[derive(Bind)]` so that they can be checked against a
+/// shader with `ShaderReflection::validate_bind_layout`
+pub trait BindLayoutEntries: BindLayout {
+    fn entries() -> Vec<wgpu::BindGroupLayoutEntry>;
+}
+
 pub mod create_bind {
     #[allow(unused)]
     #[macro_export]
@@ -28,19 +41,60 @@ pub mod create_bind {
     #[allow(unused)]
     #[macro_export]
     macro_rules! bind {
+        (@swap $field:ident: $ty:ty) => {
+            create_bind::paste! {
+                /// Replaces the resource and rebuilds the bind group with it,
+                /// returning the previous one
+                #[inline]
+                pub fn [<set_ $field>](&mut self, render_context: &RenderContext, $field: $ty) -> $ty {
+                    let previous = std::mem::replace(&mut self.$field, $field);
+                    self.refresh(render_context);
+                    previous
+                }
+
+                /// Replaces the resource and returns the previous one, the bind
+                /// group is rebuilt by the next `refresh_if_dirty` so that several
+                /// resources can be replaced with a single rebuild
+                #[inline]
+                pub fn [<replace_ $field>](&mut self, $field: $ty) -> $ty {
+                    self.dirty = true;
+                    std::mem::replace(&mut self.$field, $field)
+                }
+
+                /// Borrows the resource to change its contents, such as to write
+                /// to a buffer, which does not need the bind group to be rebuilt
+                ///
+                /// If the resource itself is replaced, as when resizing a texture,
+                /// call `mark_dirty` or `refresh` afterwards
+                #[inline]
+                pub fn [<$field _mut>](&mut self) -> &mut $ty {
+                    &mut self.$field
+                }
+            }
+        };
         ($bind:ident, $bind_layout:ident {
             $(UniformBuffers => {
                 $($buffer:ident: $ty:ty => $binding:literal for $visibility:ident,)*
             },)?
+            $(DynamicUniformBuffers => {
+                $($ubuffer:ident: $uty:ty => $ubinding:literal for $uvisibility:ident,)*
+            },)?
             $(StorageBuffers => {
-                $($sbuffer:ident: $sty:ty => $sbinding:literal for $svisibility:ident,)*
+                $($sbuffer:ident: $sty:ty => $sbinding:literal for $svisibility:ident $(use {
+                    $(read_only: $s_read_only:expr,)?
+                    $(dynamic: $s_dynamic:expr,)?
+                })?,)*
             },)?
             $(DynamicBuffers => {
-                $($dbuffer:ident: $dty:ty => $dbinding:literal for $dvisibility:ident,)*
+                $($dbuffer:ident: $dty:ty => $dbinding:literal for $dvisibility:ident $(use {
+                    $(read_only: $d_read_only:expr,)?
+                    $(dynamic: $d_dynamic:expr,)?
+                })?,)*
             },)?
             $(Textures => {
                 $($texture:ident: $DIMENSION:ty $(| for $t_count:literal)? => $t_binding:literal for $t_visibility:ident use {
                     sample_type: $sample_type:expr,
+                    $(multisampled: $multisampled:expr,)?
                 },)*
             },)?
             $(StorageTextures => {
@@ -50,7 +104,9 @@ pub mod create_bind {
                 },)*
             },)?
             $(Samplers => {
-                $($sampler:ident $(for $s_count:literal)? => $s_binding:literal for $s_visibility:ident,)*
+                $($sampler:ident $(for $s_count:literal)? => $s_binding:literal for $s_visibility:ident $(use {
+                    binding_type: $s_binding_type:expr,
+                })?,)*
             },)?
         }) => {
             #[allow(unused)]
@@ -62,6 +118,8 @@ pub mod create_bind {
 
             #[allow(unused)]
             impl $bind_layout {
+                pub const LABEL: &str = stringify!($bind_layout);
+
                 #[inline]
                 pub fn new(
                     render_context: &RenderContext,
@@ -70,93 +128,143 @@ pub mod create_bind {
 
                     Self {
                         layout: device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                            entries: &[$($(
-                                wgpu::BindGroupLayoutEntry {
-                                    binding: $binding,
-                                    visibility: wgpu::ShaderStages::$visibility,
-                                    ty: wgpu::BindingType::Buffer {
-                                        ty: wgpu::BufferBindingType::Uniform,
-                                        has_dynamic_offset: false,
-                                        min_binding_size: None,
-                                    },
-                                    count: None,
-                                },
-                            )*)?
-                            $($(
-                                wgpu::BindGroupLayoutEntry {
-                                    binding: $sbinding,
-                                    visibility: wgpu::ShaderStages::$svisibility,
-                                    ty: wgpu::BindingType::Buffer {
-                                        ty: wgpu::BufferBindingType::Storage {
-                                            read_only: true
-                                        },
-                                        has_dynamic_offset: false,
-                                        min_binding_size: None,
-                                    },
-                                    count: None,
+                            entries: &<Self as BindLayoutEntries>::entries(),
+                            label: Some(Self::LABEL),
+                        }),
+                    }
+                }
+            }
+
+            #[allow(unused)]
+            impl $bind_layout {
+                #[inline]
+                pub async fn new_checked(
+                    render_context: &RenderContext,
+                ) -> Result<Self, ValidationError> {
+                    render_context
+                        .capture_validation(|| Self::new(render_context))
+                        .await
+                }
+            }
+
+            impl BindLayoutEntries for $bind_layout {
+                fn entries() -> Vec<wgpu::BindGroupLayoutEntry> {
+                    vec![$($(
+                            wgpu::BindGroupLayoutEntry {
+                                binding: $binding,
+                                visibility: wgpu::ShaderStages::$visibility,
+                                ty: wgpu::BindingType::Buffer {
+                                    ty: wgpu::BufferBindingType::Uniform,
+                                    has_dynamic_offset: false,
+                                    min_binding_size: None,
                                 },
-                            )*)?
-                            $($(
-                                wgpu::BindGroupLayoutEntry {
-                                    binding: $dbinding,
-                                    visibility: wgpu::ShaderStages::$dvisibility,
-                                    ty: wgpu::BindingType::Buffer {
-                                        ty: wgpu::BufferBindingType::Storage {
-                                            read_only: false
-                                        },
-                                        has_dynamic_offset: true,
-                                        min_binding_size: None,
-                                    },
-                                    count: None,
+                                count: None,
+                            },
+                        )*)?
+                        $($(
+                            wgpu::BindGroupLayoutEntry {
+                                binding: $ubinding,
+                                visibility: wgpu::ShaderStages::$uvisibility,
+                                ty: wgpu::BindingType::Buffer {
+                                    ty: wgpu::BufferBindingType::Uniform,
+                                    has_dynamic_offset: true,
+                                    min_binding_size: std::num::NonZeroU64::new(
+                                        std::mem::size_of::<$uty>() as u64
+                                    ),
                                 },
-                            )*)?
-                            $($(
-                                wgpu::BindGroupLayoutEntry {
-                                    binding: $t_binding,
-                                    visibility: wgpu::ShaderStages::$t_visibility,
-                                    ty: wgpu::BindingType::Texture {
-                                        sample_type: $sample_type,
-                                        view_dimension: wgpu::TextureViewDimension::D2,
-                                        multisampled: false,
+                                count: None,
+                            },
+                        )*)?
+                        $($(
+                            wgpu::BindGroupLayoutEntry {
+                                binding: $sbinding,
+                                visibility: wgpu::ShaderStages::$svisibility,
+                                ty: wgpu::BindingType::Buffer {
+                                    ty: wgpu::BufferBindingType::Storage {
+                                        read_only: create_bind::unwrap_or_default!(
+                                            $($($s_read_only)?)?,
+                                            true
+                                        ),
                                     },
-                                    count: create_bind::unwrap_or_default!(
-                                        $(Some(std::num::NonZero::new($t_count).unwrap()))?,
-                                        None
+                                    has_dynamic_offset: create_bind::unwrap_or_default!(
+                                        $($($s_dynamic)?)?,
+                                        false
                                     ),
+                                    min_binding_size: None,
                                 },
-                            )*)?
-                            $($(
-                                wgpu::BindGroupLayoutEntry {
-                                    binding: $s_t_binding,
-                                    visibility: wgpu::ShaderStages::$s_t_visibility,
-                                    ty: wgpu::BindingType::StorageTexture {
-                                        access: $texture_access,
-                                        format: $texture_format,
-                                        view_dimension: wgpu::TextureViewDimension::D2,
+                                count: None,
+                            },
+                        )*)?
+                        $($(
+                            wgpu::BindGroupLayoutEntry {
+                                binding: $dbinding,
+                                visibility: wgpu::ShaderStages::$dvisibility,
+                                ty: wgpu::BindingType::Buffer {
+                                    ty: wgpu::BufferBindingType::Storage {
+                                        read_only: create_bind::unwrap_or_default!(
+                                            $($($d_read_only)?)?,
+                                            false
+                                        ),
                                     },
-                                    count: create_bind::unwrap_or_default!(
-                                        $(Some(std::num::NonZero::new($s_t_count).unwrap()))?,
-                                        None
+                                    has_dynamic_offset: create_bind::unwrap_or_default!(
+                                        $($($d_dynamic)?)?,
+                                        true
                                     ),
+                                    min_binding_size: None,
                                 },
-                            )*)?
-                            $($(
-                                wgpu::BindGroupLayoutEntry {
-                                    binding: $s_binding,
-                                    visibility: wgpu::ShaderStages::$s_visibility,
-                                    ty: wgpu::BindingType::Sampler(
-                                        wgpu::SamplerBindingType::Filtering
-                                    ),
-                                    count: create_bind::unwrap_or_default!(
-                                        $(Some(std::num::NonZero::new($s_count).unwrap()))?,
-                                        None
+                                count: None,
+                            },
+                        )*)?
+                        $($(
+                            wgpu::BindGroupLayoutEntry {
+                                binding: $t_binding,
+                                visibility: wgpu::ShaderStages::$t_visibility,
+                                ty: wgpu::BindingType::Texture {
+                                    sample_type: $sample_type,
+                                    view_dimension: <$DIMENSION as TextureDimension>::VIEW_DIMENSION,
+                                    multisampled: create_bind::unwrap_or_default!(
+                                        $($multisampled)?,
+                                        false
                                     ),
                                 },
-                            )*)?
-                            ],
-                            label: None,
-                        }),
-                    }
+                                count: create_bind::unwrap_or_default!(
+                                    $(Some(std::num::NonZero::new($t_count).unwrap()))?,
+                                    None
+                                ),
+                            },
+                        )*)?
+                        $($(
+                            wgpu::BindGroupLayoutEntry {
+                                binding: $s_t_binding,
+                                visibility: wgpu::ShaderStages::$s_t_visibility,
+                                ty: wgpu::BindingType::StorageTexture {
+                                    access: $texture_access,
+                                    format: $texture_format,
+                                    view_dimension: <$S_DIMENSION as TextureDimension>::VIEW_DIMENSION,
+                                },
+                                count: create_bind::unwrap_or_default!(
+                                    $(Some(std::num::NonZero::new($s_t_count).unwrap()))?,
+                                    None
+                                ),
+                            },
+                        )*)?
+                        $($(
+                            wgpu::BindGroupLayoutEntry {
+                                binding: $s_binding,
+                                visibility: wgpu::ShaderStages::$s_visibility,
+                                ty: wgpu::BindingType::Sampler(
+                                    create_bind::unwrap_or_default!(
+                                        $($s_binding_type)?,
+                                        wgpu::SamplerBindingType::Filtering
+                                    )
+                                ),
+                                count: create_bind::unwrap_or_default!(
+                                    $(Some(std::num::NonZero::new($s_count).unwrap()))?,
+                                    None
+                                ),
+                            },
+                        )*)?
+                    ]
                 }
             }
 
@@ -172,9 +280,13 @@ pub mod create_bind {
             pub struct $bind {
                 bind_group: wgpu::BindGroup,
                 layout: $bind_layout,
+                dirty: bool,
                 $($(
                     pub $buffer: UniformBuffer<$ty>,
                 )*)?
+                $($(
+                    pub $ubuffer: DynamicUniformBuffer<$uty>,
+                )*)?
                 $($(
                     pub $sbuffer: StorageBuffer<$sty>,
                 )*)?
@@ -203,6 +315,8 @@ pub mod create_bind {
 
             #[allow(unused)]
             impl $bind {
+                pub const LABEL: &str = stringify!($bind);
+
                 #[inline]
                 pub fn new(
                     render_context: &RenderContext,
@@ -210,12 +324,15 @@ pub mod create_bind {
                     $($(
                         $buffer: UniformBuffer<$ty>,
                     )*)?
+                    $($(
+                        $ubuffer: DynamicUniformBuffer<$uty>,
+                    )*)?
                     $($(
                         $sbuffer: StorageBuffer<$sty>,
                     )*)?
                     $($(
                         $dbuffer: DynamicBuffer<$dty>,
-                    )?)?
+                    )*)?
                     $($(
                         $texture: RawTexture<$DIMENSION>,
                     )*)?
@@ -237,6 +354,12 @@ pub mod create_bind {
                                     resource: unsafe { $buffer.wgpu_buffer() }.as_entire_binding(),
                                 }
                             ,)*)?
+                            $($(
+                                wgpu::BindGroupEntry {
+                                    binding: $ubinding,
+                                    resource: $ubuffer.binding(),
+                                }
+                            ,)*)?
                             $($(
                                 wgpu::BindGroupEntry {
                                     binding: $sbinding,
@@ -253,7 +376,7 @@ pub mod create_bind {
                                 wgpu::BindGroupEntry {
                                     binding: $t_binding,
                                     resource: wgpu::BindingResource::TextureView(
-                                        unsafe { &$texture.view() }
+                                        unsafe { $texture.view() }.inner()
                                     ),
                                 }
                             ,)*)?
@@ -261,7 +384,7 @@ pub mod create_bind {
                                 wgpu::BindGroupEntry {
                                     binding: $s_t_binding,
                                     resource: wgpu::BindingResource::TextureView(
-                                        unsafe { &$s_texture.view() }
+                                        unsafe { $s_texture.view() }.inner()
                                     ),
                                 }
                             ,)*)?
@@ -274,21 +397,25 @@ pub mod create_bind {
                                 }
                             ,)*)?
                         ],
-                        label: None,
+                        label: Some(Self::LABEL),
                     });
 
                     Self {
                         bind_group,
                         layout,
+                        dirty: false,
                         $($(
                             $buffer,
                         )*)?
+                        $($(
+                            $ubuffer,
+                        )*)?
                         $($(
                             $sbuffer,
                         )*)?
                         $($(
                             $dbuffer,
-                        )?)?
+                        )*)?
                         $($(
                             $texture,
                         )*)?
@@ -301,6 +428,47 @@ pub mod create_bind {
                     }
                 }
 
+                #[inline]
+                pub async fn new_checked(
+                    render_context: &RenderContext,
+                    layout: $bind_layout,
+                    $($(
+                        $buffer: UniformBuffer<$ty>,
+                    )*)?
+                    $($(
+                        $ubuffer: DynamicUniformBuffer<$uty>,
+                    )*)?
+                    $($(
+                        $sbuffer: StorageBuffer<$sty>,
+                    )*)?
+                    $($(
+                        $dbuffer: DynamicBuffer<$dty>,
+                    )*)?
+                    $($(
+                        $texture: RawTexture<$DIMENSION>,
+                    )*)?
+                    $($(
+                        $s_texture: RawTexture<$S_DIMENSION>,
+                    )*)?
+                    $($(
+                        $sampler: Sampler,
+                    )*)?
+                ) -> Result<Self, ValidationError> {
+                    render_context
+                        .capture_validation(|| Self::new(
+                            render_context,
+                            layout,
+                            $($($buffer,)*)?
+                            $($($ubuffer,)*)?
+                            $($($sbuffer,)*)?
+                            $($($dbuffer,)*)?
+                            $($($texture,)*)?
+                            $($($s_texture,)*)?
+                            $($($sampler,)*)?
+                        ))
+                        .await
+                }
+
                 #[inline]
                 pub fn refresh(&mut self, render_context: &RenderContext) {
                     let device = unsafe { render_context.device() };
@@ -314,6 +482,12 @@ pub mod create_bind {
                                     resource: unsafe { self.$buffer.wgpu_buffer() }.as_entire_binding(),
                                 }
                             ,)*)?
+                            $($(
+                                wgpu::BindGroupEntry {
+                                    binding: $ubinding,
+                                    resource: self.$ubuffer.binding(),
+                                }
+                            ,)*)?
                             $($(
                                 wgpu::BindGroupEntry {
                                     binding: $sbinding,
@@ -325,12 +499,12 @@ pub mod create_bind {
                                     binding: $dbinding,
                                     resource: unsafe { self.$dbuffer.wgpu_buffer() }.as_entire_binding(),
                                 }
-                            ,)?)?
+                            ,)*)?
                             $($(
                                 wgpu::BindGroupEntry {
                                     binding: $t_binding,
                                     resource: wgpu::BindingResource::TextureView(
-                                        unsafe { &self.$texture.view() }
+                                        unsafe { self.$texture.view() }.inner()
                                     ),
                                 }
                             ,)*)?
@@ -338,7 +512,7 @@ pub mod create_bind {
                                 wgpu::BindGroupEntry {
                                     binding: $s_t_binding,
                                     resource: wgpu::BindingResource::TextureView(
-                                        unsafe { &self.$s_texture.view() }
+                                        unsafe { self.$s_texture.view() }.inner()
                                     ),
                                 }
                             ,)*)?
@@ -351,10 +525,69 @@ pub mod create_bind {
                                 }
                             ,)*)?
                         ],
-                        label: None,
+                        label: Some(Self::LABEL),
                     });
 
                     self.bind_group = new_bind_group;
+                    self.dirty = false;
+                }
+
+                /// Marks the bind group to be rebuilt by the next
+                /// `refresh_if_dirty`, after a resource has been replaced in place
+                #[inline(always)]
+                pub const fn mark_dirty(&mut self) {
+                    self.dirty = true;
+                }
+
+                #[inline(always)]
+                pub const fn is_dirty(&self) -> bool {
+                    self.dirty
+                }
+
+                /// Changes any number of resources through `update` and rebuilds
+                /// the bind group once afterwards
+                #[inline]
+                pub fn update<R>(
+                    &mut self,
+                    render_context: &RenderContext,
+                    update: impl FnOnce(&mut Self) -> R,
+                ) -> R {
+                    let result = update(self);
+                    self.refresh(render_context);
+                    result
+                }
+
+                /// Rebuilds the bind group once if any resource has been replaced
+                /// or marked dirty, or any dynamic buffer reallocated, since it
+                /// was last built
+                #[inline]
+                pub fn refresh_if_dirty(&mut self, render_context: &RenderContext) -> bool {
+                    let mut dirty = self.dirty;
+                    $($(
+                        dirty |= self.$dbuffer.take_reallocated();
+                    )*)?
+
+                    if dirty {
+                        self.refresh(render_context);
+                    }
+
+                    dirty
+                }
+
+                /// Refreshes the bind group if any of the dynamic buffers
+                /// have been reallocated since it was created
+                #[inline]
+                pub fn refresh_if_reallocated(&mut self, render_context: &RenderContext) -> bool {
+                    let mut reallocated = false;
+                    $($(
+                        reallocated |= self.$dbuffer.take_reallocated();
+                    )*)?
+
+                    if reallocated {
+                        self.refresh(render_context);
+                    }
+
+                    reallocated
                 }
 
                 $($(
@@ -362,6 +595,17 @@ pub mod create_bind {
                     pub const fn $buffer(&self) -> &UniformBuffer<$ty> {
                         &self.$buffer
                     }
+
+                    create_bind::bind!(@swap $buffer: UniformBuffer<$ty>);
+                )*)?
+
+                $($(
+                    #[inline(always)]
+                    pub const fn $ubuffer(&self) -> &DynamicUniformBuffer<$uty> {
+                        &self.$ubuffer
+                    }
+
+                    create_bind::bind!(@swap $ubuffer: DynamicUniformBuffer<$uty>);
                 )*)?
 
                 $($(
@@ -369,6 +613,8 @@ pub mod create_bind {
                     pub const fn $sbuffer(&self) -> &StorageBuffer<$sty> {
                         &self.$sbuffer
                     }
+
+                    create_bind::bind!(@swap $sbuffer: StorageBuffer<$sty>);
                 )*)?
 
                 $($(
@@ -376,13 +622,17 @@ pub mod create_bind {
                     pub const fn $dbuffer(&self) -> &DynamicBuffer<$dty> {
                         &self.$dbuffer
                     }
-                )?)?
+
+                    create_bind::bind!(@swap $dbuffer: DynamicBuffer<$dty>);
+                )*)?
 
                 $($(
                     #[inline(always)]
                     pub const fn $texture(&self) -> &RawTexture<$DIMENSION> {
                         &self.$texture
                     }
+
+                    create_bind::bind!(@swap $texture: RawTexture<$DIMENSION>);
                 )*)?
 
                 $($(
@@ -390,6 +640,8 @@ pub mod create_bind {
                     pub const fn $s_texture(&self) -> &RawTexture<$S_DIMENSION> {
                         &self.$s_texture
                     }
+
+                    create_bind::bind!(@swap $s_texture: RawTexture<$S_DIMENSION>);
                 )*)?
 
                 $($(
@@ -397,6 +649,8 @@ pub mod create_bind {
                     pub const fn $sampler(&self) -> &Sampler {
                         &self.$sampler
                     }
+
+                    create_bind::bind!(@swap $sampler: Sampler);
                 )*)?
             }
 
@@ -413,10 +667,46 @@ pub mod create_bind {
                     &self.layout
                 }
             }
+
+            // labels to create each resource with, as `<bind>.<field>`
+            create_bind::paste! {
+                #[allow(unused)]
+                impl $bind {
+                    $($(
+                        pub const [<$buffer:upper _LABEL>]: &str =
+                            concat!(stringify!($bind), ".", stringify!($buffer));
+                    )*)?
+                    $($(
+                        pub const [<$ubuffer:upper _LABEL>]: &str =
+                            concat!(stringify!($bind), ".", stringify!($ubuffer));
+                    )*)?
+                    $($(
+                        pub const [<$sbuffer:upper _LABEL>]: &str =
+                            concat!(stringify!($bind), ".", stringify!($sbuffer));
+                    )*)?
+                    $($(
+                        pub const [<$dbuffer:upper _LABEL>]: &str =
+                            concat!(stringify!($bind), ".", stringify!($dbuffer));
+                    )*)?
+                    $($(
+                        pub const [<$texture:upper _LABEL>]: &str =
+                            concat!(stringify!($bind), ".", stringify!($texture));
+                    )*)?
+                    $($(
+                        pub const [<$s_texture:upper _LABEL>]: &str =
+                            concat!(stringify!($bind), ".", stringify!($s_texture));
+                    )*)?
+                    $($(
+                        pub const [<$sampler:upper _LABEL>]: &str =
+                            concat!(stringify!($bind), ".", stringify!($sampler));
+                    )*)?
+                }
+            }
         };
     }
 
     pub use bind;
+    pub use paste::paste;
     pub use unwrap_or_default;
 }
 
@@ -437,6 +727,75 @@ mod bind_tests {
         },
     });
 
+    create_bind::bind!(ComputeBind, ComputeBindLayout {
+        StorageBuffers => {
+            input: f32 => 0 for COMPUTE,
+            output: f32 => 1 for COMPUTE use {
+                read_only: false,
+            },
+        },
+        DynamicBuffers => {
+            lights: f32 => 2 for COMPUTE use {
+                read_only: true,
+                dynamic: false,
+            },
+            particles: f32 => 3 for COMPUTE use {
+                dynamic: false,
+            },
+        },
+    });
+
+    create_bind::bind!(SkyBind, SkyBindLayout {
+        Textures => {
+            sky: TextureCube => 0 for FRAGMENT use {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+            },
+            volume: Texture3D => 1 for FRAGMENT use {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+            },
+            layers: Texture2DArray => 2 for FRAGMENT use {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                multisampled: false,
+            },
+        },
+    });
+
+    create_bind::bind!(ShadowBind, ShadowBindLayout {
+        Textures => {
+            shadow_map: Texture2D => 0 for FRAGMENT use {
+                sample_type: Texture::DEPTH_SAMPLE_TYPE,
+            },
+        },
+        Samplers => {
+            shadow_sampler => 1 for FRAGMENT use {
+                binding_type: Texture::DEPTH_SAMPLER_BINDING_TYPE,
+            },
+            point_sampler => 2 for FRAGMENT use {
+                binding_type: wgpu::SamplerBindingType::NonFiltering,
+            },
+        },
+    });
+
+    #[derive(Debug, Bind)]
+    struct MaterialBind {
+        bind_group: wgpu::BindGroup,
+        layout: MaterialBindLayout,
+        #[sampler(2, FRAGMENT)]
+        sampler: Sampler,
+        #[texture(1, FRAGMENT, sample_type = wgpu::TextureSampleType::Float { filterable: true })]
+        diffuse: RawTexture<Texture2D>,
+        #[uniform(0, VERTEX | FRAGMENT)]
+        tint: UniformBuffer<[f32; 4]>,
+        #[storage(3, VERTEX | COMPUTE)]
+        offsets: StorageBuffer<f32>,
+    }
+
+    create_bind::bind!(ObjectBind, ObjectBindLayout {
+        DynamicUniformBuffers => {
+            model: [[f32; 4]; 4] => 0 for VERTEX,
+        },
+    });
+
     #[tokio::test]
     async fn test_bind() {
         let render_context = RenderContext::new(RenderContextConfig::default()).await;
@@ -446,11 +805,12 @@ mod bind_tests {
         let bind = SizeBind::new(
             &render_context,
             bind_layout,
-            UniformBuffer::new_init(&render_context, &0.0),
-            UniformBuffer::new_init(&render_context, &0.0),
-            StorageBuffer::new_init(&render_context, &[0.0, 1.0, 2.0]),
+            UniformBuffer::new_init(&render_context, Some(SizeBind::WIDTH_LABEL), &0.0),
+            UniformBuffer::new_init(&render_context, Some(SizeBind::HEIGHT_LABEL), &0.0),
+            StorageBuffer::new_init(&render_context, Some(SizeBind::A_LABEL), &[0.0, 1.0, 2.0]),
             DynamicBuffer::new_init(
                 &render_context,
+                Some(SizeBind::B_LABEL),
                 &[0.0, 1.0, 2.0],
                 std::num::NonZeroU64::new(4),
             ),
@@ -458,6 +818,273 @@ mod bind_tests {
 
         drop(bind);
     }
+
+    #[test]
+    fn test_field_labels() {
+        assert_eq!(SizeBind::WIDTH_LABEL, "SizeBind.width");
+        assert_eq!(ComputeBind::LIGHTS_LABEL, "ComputeBind.lights");
+        assert_eq!(SkyBind::VOLUME_LABEL, "SkyBind.volume");
+        assert_eq!(
+            ShadowBind::SHADOW_SAMPLER_LABEL,
+            "ShadowBind.shadow_sampler"
+        );
+        assert_eq!(ObjectBind::MODEL_LABEL, "ObjectBind.model");
+        assert_eq!(MaterialBind::DIFFUSE_LABEL, "MaterialBind.diffuse");
+        assert_eq!(MaterialBind::TINT_LABEL, "MaterialBind.tint");
+    }
+
+    #[tokio::test]
+    async fn test_bind_checked() {
+        let render_context = RenderContext::new(RenderContextConfig::default()).await;
+
+        let bind_layout = SizeBindLayout::new_checked(&render_context).await.unwrap();
+
+        let bind = SizeBind::new_checked(
+            &render_context,
+            bind_layout,
+            UniformBuffer::new_init(&render_context, None, &0.0),
+            UniformBuffer::new_init(&render_context, None, &0.0),
+            StorageBuffer::new_init(&render_context, None, &[0.0, 1.0, 2.0]),
+            DynamicBuffer::new_init(
+                &render_context,
+                None,
+                &[0.0, 1.0, 2.0],
+                std::num::NonZeroU64::new(4),
+            ),
+        )
+        .await;
+
+        assert!(bind.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_bind_dynamic_uniform() {
+        let render_context = RenderContext::new(RenderContextConfig::default()).await;
+
+        let bind_layout = ObjectBindLayout::new_checked(&render_context)
+            .await
+            .unwrap();
+
+        let mut models =
+            DynamicUniformBuffer::new(&render_context, None, std::num::NonZeroU64::new(2).unwrap());
+
+        let first = models.push(&render_context, &[[1.0; 4]; 4]);
+        let second = models.push(&render_context, &[[2.0; 4]; 4]);
+
+        assert_eq!(first, Ok(0));
+        assert_eq!(second, Ok(models.stride()));
+        assert_eq!(
+            models.push(&render_context, &[[3.0; 4]; 4]),
+            Err(CapacityError { capacity: 2 })
+        );
+
+        models.clear();
+        assert_eq!(models.push(&render_context, &[[3.0; 4]; 4]), Ok(0));
+
+        let bind = ObjectBind::new_checked(&render_context, bind_layout, models).await;
+
+        assert!(bind.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_bind_access_modes() {
+        let render_context = RenderContext::new(RenderContextConfig::default()).await;
+
+        let bind_layout = ComputeBindLayout::new_checked(&render_context)
+            .await
+            .unwrap();
+
+        let bind = ComputeBind::new_checked(
+            &render_context,
+            bind_layout,
+            StorageBuffer::new_init(&render_context, None, &[0.0, 1.0, 2.0]),
+            StorageBuffer::new_init(&render_context, None, &[0.0; 3]),
+            DynamicBuffer::new_init(&render_context, None, &[0.0], None),
+            DynamicBuffer::new_init(&render_context, None, &[0.0], None),
+        )
+        .await;
+
+        assert!(bind.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_bind_texture_dimensions() {
+        let render_context = RenderContext::new(RenderContextConfig::default()).await;
+
+        let size = |depth_or_array_layers| wgpu::Extent3d {
+            width: 4,
+            height: 4,
+            depth_or_array_layers,
+        };
+
+        let bind_layout = SkyBindLayout::new_checked(&render_context).await.unwrap();
+
+        let bind = SkyBind::new_checked(
+            &render_context,
+            bind_layout,
+            RawTexture::new(&render_context, size(6), &TextureConfig::default()),
+            RawTexture::new(&render_context, size(4), &TextureConfig::default()),
+            RawTexture::new(&render_context, size(3), &TextureConfig::default()),
+        )
+        .await;
+
+        assert!(bind.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_bind_derive() {
+        let render_context = RenderContext::new(RenderContextConfig::default()).await;
+
+        let diffuse = RawTexture::new(
+            &render_context,
+            wgpu::Extent3d {
+                width: 4,
+                height: 4,
+                depth_or_array_layers: 1,
+            },
+            &TextureConfig::default(),
+        );
+
+        let bind_layout = MaterialBindLayout::new_checked(&render_context)
+            .await
+            .unwrap();
+
+        let bind = MaterialBind::new_checked(
+            &render_context,
+            bind_layout,
+            Sampler::new(&render_context, &wgpu::SamplerDescriptor::default()),
+            diffuse,
+            UniformBuffer::new_init(&render_context, None, &[1.0; 4]),
+            StorageBuffer::new_init(&render_context, None, &[0.0, 1.0]),
+        )
+        .await;
+
+        let mut bind = bind.unwrap();
+
+        let tint = UniformBuffer::new_init(&render_context, None, &[0.5; 4]);
+        let swapped = render_context
+            .capture_validation(|| bind.set_tint(&render_context, tint))
+            .await;
+        assert!(swapped.is_ok());
+
+        let offsets = StorageBuffer::new_init(&render_context, None, &[2.0, 3.0]);
+        let sampler = Sampler::new(&render_context, &wgpu::SamplerDescriptor::default());
+        bind.update(&render_context, |bind| {
+            *bind.offsets_mut() = offsets;
+            *bind.sampler_mut() = sampler;
+        });
+    }
+
+    #[tokio::test]
+    async fn test_bind_shadow_sampling() {
+        let render_context = RenderContext::new(RenderContextConfig::default()).await;
+
+        let depth = Texture::create_depth_texture_sized(&render_context, 4, 4);
+
+        let point_sampler = Sampler::new(
+            &render_context,
+            &wgpu::SamplerDescriptor {
+                mag_filter: wgpu::FilterMode::Nearest,
+                min_filter: wgpu::FilterMode::Nearest,
+                mipmap_filter: wgpu::FilterMode::Nearest,
+                ..default()
+            },
+        );
+
+        let bind_layout = ShadowBindLayout::new_checked(&render_context)
+            .await
+            .unwrap();
+
+        let bind = ShadowBind::new_checked(
+            &render_context,
+            bind_layout,
+            depth.texture,
+            depth.sampler,
+            point_sampler,
+        )
+        .await;
+
+        assert!(bind.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_bind_growable() {
+        let render_context = RenderContext::new(RenderContextConfig::default()).await;
+
+        let bind_layout = SizeBindLayout::new(&render_context);
+
+        let mut bind = SizeBind::new(
+            &render_context,
+            bind_layout,
+            UniformBuffer::new_init(&render_context, None, &0.0),
+            UniformBuffer::new_init(&render_context, None, &0.0),
+            StorageBuffer::new_init(&render_context, None, &[0.0, 1.0, 2.0]),
+            DynamicBuffer::new_init(
+                &render_context,
+                None,
+                &[0.0, 1.0, 2.0],
+                std::num::NonZeroU64::new(4),
+            )
+            .growable(),
+        );
+
+        assert!(!bind.refresh_if_reallocated(&render_context));
+
+        bind.b.write_at_offset(&render_context, &[3.0, 4.0, 5.0], 3);
+
+        assert!(bind.b.max_size() >= 6);
+        assert!(bind.refresh_if_reallocated(&render_context));
+        assert_eq!(
+            bind.b.read_blocking(&render_context),
+            [0.0, 1.0, 2.0, 3.0, 4.0, 5.0]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bind_swap() {
+        let render_context = RenderContext::new(RenderContextConfig::default()).await;
+
+        let bind_layout = SizeBindLayout::new(&render_context);
+
+        let mut bind = SizeBind::new(
+            &render_context,
+            bind_layout,
+            UniformBuffer::new_init(&render_context, None, &0.0),
+            UniformBuffer::new_init(&render_context, None, &0.0),
+            StorageBuffer::new_init(&render_context, None, &[0.0, 1.0, 2.0]),
+            DynamicBuffer::new_init(
+                &render_context,
+                None,
+                &[0.0, 1.0, 2.0],
+                std::num::NonZeroU64::new(4),
+            ),
+        );
+
+        assert!(!bind.refresh_if_dirty(&render_context));
+
+        // writing to a buffer keeps the same binding
+        bind.a_mut().write(&render_context, &[3.0, 4.0, 5.0]);
+        assert!(!bind.is_dirty());
+
+        bind.replace_width(UniformBuffer::new_init(&render_context, None, &1.0));
+        bind.replace_height(UniformBuffer::new_init(&render_context, None, &2.0));
+
+        assert!(bind.is_dirty());
+        assert!(bind.refresh_if_dirty(&render_context));
+        assert!(!bind.is_dirty());
+        assert!(!bind.refresh_if_dirty(&render_context));
+
+        let previous = bind.set_width(
+            &render_context,
+            UniformBuffer::new_init(&render_context, None, &3.0),
+        );
+        assert!(!bind.is_dirty());
+        drop(previous);
+
+        let height = UniformBuffer::new_init(&render_context, None, &4.0);
+        bind.update(&render_context, |bind| *bind.height_mut() = height);
+        assert!(!bind.is_dirty());
+    }
 }
 
 #[cfg(test)]
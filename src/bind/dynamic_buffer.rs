@@ -1,4 +1,6 @@
-use std::{mem, num::NonZeroU64, slice};
+use std::{mem, num::NonZeroU64, ops::Range, slice};
+
+use super::readback;
 
 use crate::prelude::*;
 
@@ -6,24 +8,35 @@ use crate::prelude::*;
 pub struct DynamicBuffer<T: 'static> {
     buffer: wgpu::Buffer,
     size: u64,
+    shadow: Option<Vec<u8>>,
+    label: Option<Box<str>>,
+    growable: bool,
+    reallocated: bool,
     _marker: PhantomData<T>,
 }
 
 impl<T: 'static> DynamicBuffer<T> {
     #[inline]
-    pub fn new(render_context: &RenderContext, max_size: NonZeroU64) -> Self {
+    pub fn new(render_context: &RenderContext, label: Option<&str>, max_size: NonZeroU64) -> Self {
         let device = unsafe { render_context.device() };
 
         let buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: None,
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
-            size: mem::size_of::<T>() as u64 * max_size.get(),
+            label,
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
+            size: (mem::size_of::<T>() as u64 * max_size.get())
+                .next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT),
             mapped_at_creation: false,
         });
 
         Self {
             size: 0,
+            shadow: None,
             buffer,
+            label: label.map(Into::into),
+            growable: false,
+            reallocated: false,
             _marker: PhantomData,
         }
     }
@@ -31,12 +44,15 @@ impl<T: 'static> DynamicBuffer<T> {
     #[inline]
     pub fn new_init(
         render_context: &RenderContext,
+        label: Option<&str>,
         value: &[T],
         max_size: Option<NonZeroU64>,
     ) -> Self
     where
-        T: bytemuck::NoUninit,
+        T: StorageData,
     {
+        let () = T::CHECK;
+
         let size = match max_size {
             Some(size) => size.get(),
             None => value.len() as u64,
@@ -47,22 +63,26 @@ impl<T: 'static> DynamicBuffer<T> {
         let device = unsafe { render_context.device() };
 
         let buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: None,
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
-            size: mem::size_of::<T>() as u64 * size,
+            label,
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
+            size: (mem::size_of::<T>() as u64 * size).next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT),
             mapped_at_creation: true,
         });
 
-        buffer
-            .slice(..std::mem::size_of_val(value) as u64)
-            .get_mapped_range_mut()
+        buffer.slice(..).get_mapped_range_mut()[..mem::size_of_val(value)]
             .copy_from_slice(bytemuck::cast_slice(value));
 
         buffer.unmap();
 
         Self {
             size: value.len() as u64,
+            shadow: None,
             buffer,
+            label: label.map(Into::into),
+            growable: false,
+            reallocated: false,
             _marker: PhantomData,
         }
     }
@@ -70,6 +90,7 @@ impl<T: 'static> DynamicBuffer<T> {
     #[inline]
     pub fn with_usage(
         render_context: &RenderContext,
+        label: Option<&str>,
         max_size: NonZeroU64,
         usage: wgpu::BufferUsages,
         mapped_at_creation: bool,
@@ -77,15 +98,20 @@ impl<T: 'static> DynamicBuffer<T> {
         let device = unsafe { render_context.device() };
 
         let buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: None,
+            label,
             usage: wgpu::BufferUsages::STORAGE | usage,
-            size: mem::size_of::<T>() as u64 * max_size.get(),
+            size: (mem::size_of::<T>() as u64 * max_size.get())
+                .next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT),
             mapped_at_creation,
         });
 
         Self {
             size: 0,
+            shadow: None,
             buffer,
+            label: label.map(Into::into),
+            growable: false,
+            reallocated: false,
             _marker: PhantomData,
         }
     }
@@ -93,13 +119,16 @@ impl<T: 'static> DynamicBuffer<T> {
     #[inline]
     pub fn with_usage_init(
         render_context: &RenderContext,
+        label: Option<&str>,
         value: &[T],
         max_size: Option<NonZeroU64>,
         usage: wgpu::BufferUsages,
     ) -> Self
     where
-        T: bytemuck::NoUninit,
+        T: StorageData,
     {
+        let () = T::CHECK;
+
         let size = match max_size {
             Some(size) => size.get(),
             None => value.len() as u64,
@@ -110,26 +139,149 @@ impl<T: 'static> DynamicBuffer<T> {
         let device = unsafe { render_context.device() };
 
         let buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: None,
+            label,
             usage: wgpu::BufferUsages::STORAGE | usage,
-            size: mem::size_of::<T>() as u64 * size,
+            size: (mem::size_of::<T>() as u64 * size).next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT),
             mapped_at_creation: true,
         });
 
-        buffer
-            .slice(..std::mem::size_of_val(value) as u64)
-            .get_mapped_range_mut()
+        buffer.slice(..).get_mapped_range_mut()[..mem::size_of_val(value)]
             .copy_from_slice(bytemuck::cast_slice(value));
 
         buffer.unmap();
 
         Self {
             size: value.len() as u64,
+            shadow: None,
             buffer,
+            label: label.map(Into::into),
+            growable: false,
+            reallocated: false,
             _marker: PhantomData,
         }
     }
 
+    /// Keeps a CPU copy of the buffer in sync with every write made through
+    /// it, which `get`, `to_vec`, `pop` and `swap_remove` read from
+    ///
+    /// The current contents are read back once to start the copy, so the
+    /// buffer must have `BufferUsages::COPY_SRC` if it is not empty
+    pub fn with_cpu_copy(mut self, render_context: &RenderContext) -> Self {
+        let len =
+            (mem::size_of::<T>() as u64 * self.size).next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT);
+
+        let mut shadow = readback::read_buffer_blocking::<u8>(render_context, &self.buffer, 0..len);
+        shadow.resize(self.buffer.size() as usize, 0);
+
+        self.shadow = Some(shadow);
+        self
+    }
+
+    #[inline(always)]
+    pub const fn has_cpu_copy(&self) -> bool {
+        self.shadow.is_some()
+    }
+
+    /// Allows writes past `max_size` to reallocate the buffer instead of panicking
+    #[inline(always)]
+    pub const fn growable(mut self) -> Self {
+        self.growable = true;
+        self
+    }
+
+    #[inline(always)]
+    pub const fn set_growable(&mut self, growable: bool) {
+        self.growable = growable;
+    }
+
+    #[inline(always)]
+    pub const fn is_growable(&self) -> bool {
+        self.growable
+    }
+
+    /// Whether the inner `wgpu::Buffer` has been replaced since the last call,
+    /// in which case any bind group using it must be refreshed
+    #[inline(always)]
+    pub const fn take_reallocated(&mut self) -> bool {
+        mem::replace(&mut self.reallocated, false)
+    }
+
+    /// Ensures there is room for at least `additional` more elements past
+    /// the current size, reallocating the buffer if there is not
+    ///
+    /// # Panics
+    /// If there is not enough room and the buffer is not `growable`
+    #[inline]
+    pub fn reserve(&mut self, render_context: &RenderContext, additional: u64) {
+        let required = self.size + additional;
+
+        if required > self.max_size() && self.growable {
+            self.grow(render_context, required);
+        } else if required > self.max_size() {
+            panic!(
+                "size ({}) + additional ({}) larger than max size ({})",
+                self.size,
+                additional,
+                self.max_size()
+            );
+        }
+    }
+
+    /// Replaces the buffer with one that can hold at least `required` elements,
+    /// copying the current contents across on the GPU
+    ///
+    /// The old buffer must have `BufferUsages::COPY_SRC`, which is included by
+    /// `new` and `new_init` but must be given to `with_usage`
+    fn grow(&mut self, render_context: &RenderContext, required: u64) {
+        let device = unsafe { render_context.device() };
+
+        let max_size = required.max(self.max_size() * 2);
+
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: self.label.as_deref(),
+            usage: self.buffer.usage(),
+            size: (mem::size_of::<T>() as u64 * max_size)
+                .next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT),
+            mapped_at_creation: false,
+        });
+
+        let copy_size = (mem::size_of::<T>() as u64 * self.size)
+            .next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT)
+            .min(self.buffer.size());
+
+        if copy_size > 0 {
+            if !self.buffer.usage().contains(wgpu::BufferUsages::COPY_SRC) {
+                panic!("dynamic buffer cannot grow without `BufferUsages::COPY_SRC`");
+            }
+
+            let mut encoder = render_context.command_encoder();
+            encoder
+                .encoder
+                .copy_buffer_to_buffer(&self.buffer, 0, &buffer, 0, copy_size);
+            encoder.submit();
+        }
+
+        if let Some(shadow) = &mut self.shadow {
+            shadow.truncate(copy_size as usize);
+            shadow.resize(buffer.size() as usize, 0);
+        }
+
+        self.buffer = buffer;
+        self.reallocated = true;
+    }
+
+    /// Shortens the buffer to `size` elements, the contents past it are
+    /// left on the GPU but are no longer part of the buffer
+    #[inline(always)]
+    pub fn truncate(&mut self, size: u64) {
+        self.size = self.size.min(size);
+    }
+
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.size = 0;
+    }
+
     /// # Safety
     /// This function is unsafe because the buffer may not be mapped
     #[inline(always)]
@@ -161,20 +313,26 @@ impl<T: 'static> DynamicBuffer<T> {
         self.buffer.slice(..mem::size_of::<T>() as u64 * self.size)
     }
 
+    /// Replaces the contents of the buffer with `data`
     #[inline(always)]
     pub fn write(&mut self, render_context: &RenderContext, data: &[T])
     where
-        T: bytemuck::NoUninit,
+        T: StorageData,
     {
+        self.clear();
         self.write_at_offset(render_context, data, 0);
     }
 
     #[inline]
     pub fn write_at_offset(&mut self, render_context: &RenderContext, data: &[T], offset: u64)
     where
-        T: bytemuck::NoUninit,
+        T: StorageData,
     {
-        if offset + data.len() as u64 > self.max_size() {
+        let required = offset + data.len() as u64;
+
+        if required > self.max_size() && self.growable {
+            self.grow(render_context, required);
+        } else if required > self.max_size() {
             panic!(
                 "offset ({}) + data len ({}) larger than max size ({})",
                 offset,
@@ -195,11 +353,20 @@ impl<T: 'static> DynamicBuffer<T> {
         data: &[T],
         offset: u64,
     ) where
-        T: bytemuck::NoUninit,
+        T: StorageData,
     {
+        let () = T::CHECK;
+
         let queue = unsafe { render_context.queue() };
 
-        self.size = offset + data.len() as u64;
+        // writing inside the buffer keeps the elements past `data`
+        self.size = self.size.max(offset + data.len() as u64);
+
+        if let Some(shadow) = &mut self.shadow {
+            let start = offset as usize * mem::size_of::<T>();
+            shadow[start..start + mem::size_of_val(data)]
+                .copy_from_slice(bytemuck::cast_slice(data));
+        }
 
         queue.write_buffer(
             &self.buffer,
@@ -209,9 +376,9 @@ impl<T: 'static> DynamicBuffer<T> {
     }
 
     #[inline]
-    pub fn set_at_offset(&self, render_context: &RenderContext, data: &T, offset: u64)
+    pub fn set_at_offset(&mut self, render_context: &RenderContext, data: &T, offset: u64)
     where
-        T: bytemuck::NoUninit,
+        T: StorageData,
     {
         if offset >= self.size() {
             panic!(
@@ -228,17 +395,24 @@ impl<T: 'static> DynamicBuffer<T> {
     /// This may make an unsafe memory access
     #[inline]
     pub unsafe fn set_at_offset_unchecked(
-        &self,
+        &mut self,
         render_context: &RenderContext,
         data: &T,
         offset: u64,
     ) where
-        T: bytemuck::NoUninit,
+        T: StorageData,
     {
+        let () = T::CHECK;
+
         let queue = unsafe { render_context.queue() };
 
         let slice = unsafe { slice::from_raw_parts(data, 1) };
 
+        if let Some(shadow) = &mut self.shadow {
+            let start = offset as usize * mem::size_of::<T>();
+            shadow[start..start + mem::size_of::<T>()].copy_from_slice(bytemuck::cast_slice(slice));
+        }
+
         queue.write_buffer(
             &self.buffer,
             offset * mem::size_of::<T>() as u64,
@@ -246,3 +420,205 @@ impl<T: 'static> DynamicBuffer<T> {
         );
     }
 }
+
+/// Editing operations that treat the buffer as a GPU-side `Vec<T>`
+///
+/// Elements are read from the CPU copy enabled by `with_cpu_copy`, so they
+/// can be read without touching the GPU and only the elements that change
+/// are uploaded. Writes made through a buffer that was mapped at creation or
+/// by shaders are not seen by the copy
+impl<T: bytemuck::Pod + StorageData> DynamicBuffer<T> {
+    /// # Panics
+    /// If the buffer has no CPU copy
+    #[inline]
+    pub fn get(&self, index: u64) -> Option<T> {
+        if index >= self.size {
+            return None;
+        }
+
+        let start = index as usize * mem::size_of::<T>();
+        Some(bytemuck::pod_read_unaligned(
+            &self.cpu_copy()[start..start + mem::size_of::<T>()],
+        ))
+    }
+
+    /// Returns the elements up to the current size from the CPU copy
+    ///
+    /// # Panics
+    /// If the buffer has no CPU copy
+    #[inline]
+    pub fn to_vec(&self) -> Vec<T> {
+        bytemuck::pod_collect_to_vec(&self.cpu_copy()[..self.size as usize * mem::size_of::<T>()])
+    }
+
+    /// # Panics
+    /// If the buffer is full and not `growable`
+    #[inline(always)]
+    pub fn push(&mut self, render_context: &RenderContext, value: T) {
+        self.extend(render_context, slice::from_ref(&value));
+    }
+
+    /// # Panics
+    /// If `values` do not fit in the buffer and it is not `growable`
+    #[inline(always)]
+    pub fn extend(&mut self, render_context: &RenderContext, values: &[T]) {
+        self.write_at_offset(render_context, values, self.size);
+    }
+
+    /// # Panics
+    /// If the buffer has no CPU copy
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        let value = self.get(self.size.checked_sub(1)?)?;
+        self.size -= 1;
+
+        Some(value)
+    }
+
+    /// Removes the element at `index` and replaces it with the last element,
+    /// uploading only the moved element
+    ///
+    /// # Panics
+    /// If `index` is out of bounds or the buffer has no CPU copy
+    pub fn swap_remove(&mut self, render_context: &RenderContext, index: u64) -> T {
+        let Some(value) = self.get(index) else {
+            panic!(
+                "swap_remove index ({}) should be less than current size ({})",
+                index, self.size
+            );
+        };
+
+        let last = self.size - 1;
+
+        if index != last {
+            let moved = self.get(last).unwrap();
+            unsafe { self.set_at_offset_unchecked(render_context, &moved, index) };
+        }
+
+        self.size = last;
+
+        value
+    }
+
+    #[inline]
+    fn cpu_copy(&self) -> &[u8] {
+        self.shadow
+            .as_deref()
+            .expect("dynamic buffer has no CPU copy, create it `with_cpu_copy`")
+    }
+}
+
+/// Reading requires the buffer to have `BufferUsages::COPY_SRC`, which is
+/// included by `new` and `new_init` but must be given to `with_usage`
+impl<T: bytemuck::Pod> DynamicBuffer<T> {
+    /// Copies the elements up to the current size into a staging buffer and reads it back
+    ///
+    /// The device is waited on from a helper thread, so awaiting this does
+    /// not keep the executor busy until the copy has finished
+    #[inline(always)]
+    pub async fn read(&self, render_context: &RenderContext) -> Vec<T> {
+        self.read_range(render_context, 0..self.size()).await
+    }
+
+    pub async fn read_range(&self, render_context: &RenderContext, range: Range<u64>) -> Vec<T> {
+        self.check_range(&range);
+
+        readback::read_buffer(render_context, &self.buffer, range).await
+    }
+
+    /// Like `read`, but blocks until the device has finished all submitted work
+    #[inline(always)]
+    pub fn read_blocking(&self, render_context: &RenderContext) -> Vec<T> {
+        self.read_range_blocking(render_context, 0..self.size())
+    }
+
+    pub fn read_range_blocking(&self, render_context: &RenderContext, range: Range<u64>) -> Vec<T> {
+        self.check_range(&range);
+
+        readback::read_buffer_blocking(render_context, &self.buffer, range)
+    }
+
+    #[inline]
+    fn check_range(&self, range: &Range<u64>) {
+        if range.start > range.end || range.end > self.size() {
+            panic!(
+                "range ({}..{}) out of bounds of current size ({})",
+                range.start,
+                range.end,
+                self.size()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod dynamic_buffer_tests {
+    use crate::prelude::*;
+
+    #[tokio::test]
+    async fn test_vec_operations() {
+        let render_context = RenderContext::new(RenderContextConfig::default()).await;
+
+        let mut buffer =
+            DynamicBuffer::<u32>::new(&render_context, None, std::num::NonZeroU64::new(2).unwrap())
+                .with_cpu_copy(&render_context)
+                .growable();
+
+        buffer.push(&render_context, 1);
+        buffer.extend(&render_context, &[2, 3, 4, 5]);
+
+        assert_eq!(buffer.pop(), Some(5));
+        assert_eq!(buffer.swap_remove(&render_context, 0), 1);
+        assert_eq!(buffer.to_vec(), [4, 2, 3]);
+
+        buffer.truncate(2);
+
+        assert_eq!(buffer.to_vec(), [4, 2]);
+        assert_eq!(buffer.read_blocking(&render_context), [4, 2]);
+
+        buffer.clear();
+
+        assert_eq!(buffer.pop(), None);
+    }
+
+    #[tokio::test]
+    async fn test_write_inside() {
+        let render_context = RenderContext::new(RenderContextConfig::default()).await;
+
+        let mut buffer = DynamicBuffer::new_init(&render_context, None, &[1u32, 2, 3, 4], None)
+            .with_cpu_copy(&render_context);
+
+        buffer.write_at_offset(&render_context, &[5, 6], 1);
+
+        assert_eq!(buffer.size(), 4);
+        assert_eq!(buffer.pop(), Some(4));
+        assert_eq!(buffer.read_blocking(&render_context), [1, 5, 6]);
+
+        buffer.write(&render_context, &[7]);
+
+        assert_eq!(buffer.to_vec(), [7]);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "larger than max size")]
+    async fn test_reserve_not_growable() {
+        let render_context = RenderContext::new(RenderContextConfig::default()).await;
+
+        let mut buffer = DynamicBuffer::new_init(&render_context, None, &[1u32, 2], None);
+
+        buffer.reserve(&render_context, 1);
+    }
+
+    #[tokio::test]
+    async fn test_cpu_copy_of_init() {
+        let render_context = RenderContext::new(RenderContextConfig::default()).await;
+
+        let buffer = DynamicBuffer::new_init(&render_context, None, &[1u32, 2, 3], None);
+        assert!(!buffer.has_cpu_copy());
+
+        let buffer = buffer.with_cpu_copy(&render_context);
+
+        assert_eq!(buffer.get(2), Some(3));
+        assert_eq!(buffer.to_vec(), [1, 2, 3]);
+    }
+}
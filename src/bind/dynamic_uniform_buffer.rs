@@ -0,0 +1,329 @@
+use std::{mem, num::NonZeroU64};
+
+use crate::prelude::*;
+
+/// Many values of `T` packed into a single uniform buffer, each one starting
+/// at a multiple of `min_uniform_buffer_offset_alignment` so that it can be
+/// selected with a dynamic offset when setting the bind group
+///
+/// Values are pushed one slot after another until the capacity is reached.
+/// All writes made before a submit land before any of its draws, so slots
+/// must not be reused until the submit that reads them has been made, after
+/// which `clear` starts pushing from the first slot again
+#[derive(Clone, Debug)]
+pub struct DynamicUniformBuffer<T: 'static> {
+    buffer: wgpu::Buffer,
+    stride: u32,
+    capacity: u32,
+    cursor: u32,
+    _marker: PhantomData<T>,
+}
+
+impl<T: 'static> DynamicUniformBuffer<T> {
+    #[inline]
+    pub fn new(render_context: &RenderContext, label: Option<&str>, capacity: NonZeroU64) -> Self {
+        Self::with_usage(
+            render_context,
+            label,
+            capacity,
+            wgpu::BufferUsages::COPY_DST,
+        )
+    }
+
+    pub fn with_usage(
+        render_context: &RenderContext,
+        label: Option<&str>,
+        capacity: NonZeroU64,
+        usage: wgpu::BufferUsages,
+    ) -> Self {
+        let device = unsafe { render_context.device() };
+
+        let stride = Self::stride_for(device);
+        let capacity = u32::try_from(capacity.get()).expect("capacity must fit in a u32");
+
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label,
+            usage: wgpu::BufferUsages::UNIFORM | usage,
+            size: stride as u64 * capacity as u64,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            buffer,
+            stride,
+            capacity,
+            cursor: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn stride_for(device: &wgpu::Device) -> u32 {
+        let alignment = device.limits().min_uniform_buffer_offset_alignment;
+
+        (mem::size_of::<T>() as u32)
+            .max(1)
+            .next_multiple_of(alignment)
+    }
+
+    /// Distance in bytes between consecutive values
+    #[inline(always)]
+    pub const fn stride(&self) -> u32 {
+        self.stride
+    }
+
+    #[inline(always)]
+    pub const fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /// Number of values pushed since the last `clear`, up to the capacity
+    #[inline(always)]
+    pub const fn len(&self) -> u32 {
+        self.cursor
+    }
+
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.cursor == 0
+    }
+
+    /// The dynamic offset that selects the value at `index`
+    #[inline(always)]
+    pub const fn offset(&self, index: u32) -> u32 {
+        index * self.stride
+    }
+
+    /// Starts pushing from the beginning of the buffer again
+    #[inline(always)]
+    pub const fn clear(&mut self) {
+        self.cursor = 0;
+    }
+
+    /// Binds a single value's worth of the buffer, to be moved along it
+    /// with a dynamic offset
+    #[inline]
+    pub fn binding(&self) -> wgpu::BindingResource<'_> {
+        wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+            buffer: &self.buffer,
+            offset: 0,
+            size: NonZeroU64::new(mem::size_of::<T>() as u64),
+        })
+    }
+
+    /// # Safety
+    /// This function is unsafe because it returns the inner `wgpu::Buffer`
+    #[inline(always)]
+    pub unsafe fn wgpu_buffer(&self) -> &wgpu::Buffer {
+        &self.buffer
+    }
+}
+
+impl<T: 'static + UniformData> DynamicUniformBuffer<T> {
+    pub fn new_init(render_context: &RenderContext, label: Option<&str>, values: &[T]) -> Self {
+        let capacity = NonZeroU64::new(values.len() as u64)
+            .expect("dynamic uniform buffer capacity cannot be zero");
+
+        let mut buffer = Self::new(render_context, label, capacity);
+        buffer.write_all(render_context, values);
+
+        buffer
+    }
+
+    /// Writes `value` into the next slot and returns its dynamic offset, or
+    /// an error once every slot has been pushed to since the last `clear`
+    #[inline]
+    pub fn push(
+        &mut self,
+        render_context: &RenderContext,
+        value: &T,
+    ) -> Result<u32, CapacityError> {
+        if self.cursor == self.capacity {
+            return Err(CapacityError {
+                capacity: self.capacity,
+            });
+        }
+
+        let index = self.cursor;
+        self.cursor += 1;
+
+        self.set(render_context, index, value);
+
+        Ok(self.offset(index))
+    }
+
+    #[inline]
+    pub fn set(&self, render_context: &RenderContext, index: u32, value: &T) {
+        if index >= self.capacity {
+            panic!(
+                "index ({}) larger than or equal to capacity ({})",
+                index, self.capacity
+            );
+        }
+
+        let () = T::CHECK;
+
+        let queue = unsafe { render_context.queue() };
+
+        let bytes = bytemuck::bytes_of(value);
+
+        // writes must be a multiple of `COPY_BUFFER_ALIGNMENT`, which the
+        // padding between slots always leaves room for
+        if bytes
+            .len()
+            .is_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT as usize)
+        {
+            queue.write_buffer(&self.buffer, self.offset(index) as u64, bytes);
+        } else {
+            let mut data = vec![
+                0u8;
+                bytes
+                    .len()
+                    .next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT as usize)
+            ];
+            data[..bytes.len()].copy_from_slice(bytes);
+
+            queue.write_buffer(&self.buffer, self.offset(index) as u64, &data);
+        }
+    }
+
+    /// Writes `values` from the start of the buffer in a single upload,
+    /// leaving the cursor after the last value
+    pub fn write_all(&mut self, render_context: &RenderContext, values: &[T]) {
+        let () = T::CHECK;
+
+        if values.len() as u64 > self.capacity as u64 {
+            panic!(
+                "values len ({}) larger than capacity ({})",
+                values.len(),
+                self.capacity
+            );
+        }
+
+        let Some(last) = values.len().checked_sub(1) else {
+            self.cursor = 0;
+            return;
+        };
+
+        let size = self.stride as usize * last + mem::size_of::<T>();
+        let mut data = vec![0u8; size.next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT as usize)];
+
+        for (slot, value) in data.chunks_mut(self.stride as usize).zip(values) {
+            slot[..mem::size_of::<T>()].copy_from_slice(bytemuck::bytes_of(value));
+        }
+
+        let queue = unsafe { render_context.queue() };
+        queue.write_buffer(&self.buffer, 0, &data);
+
+        self.cursor = values.len() as u32;
+    }
+}
+
+#[cfg(test)]
+mod dynamic_uniform_buffer_tests {
+    use crate::prelude::*;
+
+    create_bind::bind!(TintBind, TintBindLayout {
+        DynamicUniformBuffers => {
+            tint: [f32; 4] => 0 for FRAGMENT,
+        },
+    });
+
+    struct TintLayout {
+        layout: RawLayout<Void>,
+    }
+
+    impl Layout for TintLayout {
+        type VertexLayout = Void;
+
+        fn raw_layout(&self) -> &RawLayout<Void> {
+            &self.layout
+        }
+    }
+
+    const TINT_SHADER: &str = "
+        @group(0) @binding(0) var<uniform> tint: vec4<f32>;
+
+        @vertex
+        fn vs(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+            let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+            return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+        }
+
+        @fragment
+        fn fs() -> @location(0) vec4<f32> {
+            return tint;
+        }
+    ";
+
+    #[tokio::test]
+    async fn test_draw_with_offsets() {
+        let render_context = RenderContext::new(RenderContextConfig::default()).await;
+
+        let mut tints =
+            DynamicUniformBuffer::new(&render_context, None, std::num::NonZeroU64::new(2).unwrap());
+
+        let red = tints.push(&render_context, &[1.0, 0.0, 0.0, 1.0]).unwrap();
+        let blue = tints.push(&render_context, &[0.0, 0.0, 1.0, 1.0]).unwrap();
+
+        let bind_layout = TintBindLayout::new(&render_context);
+
+        let layout = TintLayout {
+            layout: RawLayout::new(
+                &render_context,
+                LayoutConfig {
+                    bind_group_layouts: &[bind_layout.wgpu_layout()],
+                    format: wgpu::TextureFormat::Rgba8Unorm,
+                    ..default()
+                },
+            ),
+        };
+
+        let bind = TintBind::new(&render_context, bind_layout, tints);
+
+        let module =
+            render_context.create_shader_module(None, wgpu::ShaderSource::Wgsl(TINT_SHADER.into()));
+
+        let pipeline = layout.create_pipeline(
+            &render_context,
+            &module,
+            ShaderConfig {
+                depth_stencil: Some(None),
+                ..default()
+            },
+        );
+
+        let target = RenderTarget::new(
+            &render_context,
+            2,
+            1,
+            &RenderTargetConfig {
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                ..default()
+            },
+        );
+
+        let mut encoder = render_context.command_encoder();
+        let mut render_pass = encoder
+            .render_target_pass(&target, None, false)
+            .create_shared_data::<TintLayout>();
+        unsafe { render_pass.inner() }.set_pipeline(&pipeline);
+
+        // one draw for each texel, each with its own tint
+        for (x, offset) in [red, blue].into_iter().enumerate() {
+            render_pass.set_dynamic_offsets(0, &bind, &[offset]);
+
+            let inner = unsafe { render_pass.inner() };
+            inner.set_viewport(x as f32, 0.0, 1.0, 1.0, 0.0, 1.0);
+            inner.draw(0..3, 0..1);
+        }
+
+        drop(render_pass);
+        encoder.submit();
+
+        assert_eq!(
+            target.read(&render_context),
+            [255, 0, 0, 255, 0, 0, 255, 255]
+        );
+    }
+}
@@ -0,0 +1,258 @@
+use std::{
+    future::Future,
+    mem,
+    ops::Range,
+    pin::Pin,
+    sync::Mutex,
+    task::{Context, Poll, Waker},
+};
+
+use crate::prelude::*;
+
+type MapResult = Result<(), wgpu::BufferAsyncError>;
+
+#[derive(Debug, Default)]
+struct MapState {
+    result: Option<MapResult>,
+    waker: Option<Waker>,
+}
+
+/// Resolves once `map_async` has called back, which wakes the task that
+/// awaits it, while the device is polled by `poll_in_background`
+struct MapFuture {
+    state: Arc<Mutex<MapState>>,
+}
+
+impl Future for MapFuture {
+    type Output = MapResult;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock().unwrap();
+
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Waits for the submitted work on a helper thread, so that the map callback
+/// fires without the executor having to poll the device
+fn poll_in_background(render_context: &RenderContext) {
+    let device = unsafe { render_context.device() }.clone();
+
+    std::thread::spawn(move || {
+        device
+            .poll(wgpu::PollType::wait_indefinitely())
+            .expect("failed to poll device");
+    });
+}
+
+/// A `MAP_READ` buffer holding a copy of a range of elements of a
+/// `COPY_SRC` buffer, which has been submitted but not yet mapped
+struct Staging<T> {
+    buffer: wgpu::Buffer,
+    skip: usize,
+    len: usize,
+    _marker: PhantomData<T>,
+}
+
+impl<T: bytemuck::Pod> Staging<T> {
+    fn copy(render_context: &RenderContext, source: &wgpu::Buffer, range: Range<u64>) -> Self {
+        let size = mem::size_of::<T>() as u64;
+
+        let start = range.start * size;
+        let end = range.end * size;
+
+        // copies must start and end on `COPY_BUFFER_ALIGNMENT`, so the range
+        // is widened and the extra bytes are skipped when reading it back,
+        // which stays within the source as buffers are allocated rounded up
+        let aligned_start = start - start % wgpu::COPY_BUFFER_ALIGNMENT;
+        let aligned_end = end.next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT);
+
+        assert!(
+            aligned_end <= source.size(),
+            "buffer size ({}) is not a multiple of `COPY_BUFFER_ALIGNMENT` and cannot be read back",
+            source.size()
+        );
+
+        let buffer = unsafe { render_context.device() }.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Readback Staging Buffer"),
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            size: aligned_end - aligned_start,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = render_context.command_encoder();
+        encoder.encoder.copy_buffer_to_buffer(
+            source,
+            aligned_start,
+            &buffer,
+            0,
+            aligned_end - aligned_start,
+        );
+        encoder.submit();
+
+        Self {
+            buffer,
+            skip: (start - aligned_start) as usize,
+            len: (end - start) as usize,
+            _marker: PhantomData,
+        }
+    }
+
+    fn map(&self) -> Arc<Mutex<MapState>> {
+        let state = Arc::new(Mutex::new(MapState::default()));
+        let callback_state = state.clone();
+
+        self.buffer
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                let mut state = callback_state.lock().unwrap();
+                state.result = Some(result);
+
+                if let Some(waker) = state.waker.take() {
+                    waker.wake();
+                }
+            });
+
+        state
+    }
+
+    fn collect(self, result: MapResult) -> Vec<T> {
+        result.expect("failed to map readback buffer");
+
+        let data = {
+            let mapped = self.buffer.slice(..).get_mapped_range();
+            bytemuck::pod_collect_to_vec(&mapped[self.skip..self.skip + self.len])
+        };
+
+        self.buffer.unmap();
+
+        data
+    }
+}
+
+pub(crate) async fn read_buffer<T: bytemuck::Pod>(
+    render_context: &RenderContext,
+    source: &wgpu::Buffer,
+    range: Range<u64>,
+) -> Vec<T> {
+    if range.is_empty() {
+        return Vec::new();
+    }
+
+    let staging = Staging::<T>::copy(render_context, source, range);
+
+    let state = staging.map();
+    poll_in_background(render_context);
+
+    let result = MapFuture { state }.await;
+
+    staging.collect(result)
+}
+
+pub(crate) fn read_buffer_blocking<T: bytemuck::Pod>(
+    render_context: &RenderContext,
+    source: &wgpu::Buffer,
+    range: Range<u64>,
+) -> Vec<T> {
+    if range.is_empty() {
+        return Vec::new();
+    }
+
+    let staging = Staging::<T>::copy(render_context, source, range);
+    let state = staging.map();
+
+    unsafe { render_context.device() }
+        .poll(wgpu::PollType::wait_indefinitely())
+        .expect("failed to poll device");
+
+    let result = state
+        .lock()
+        .unwrap()
+        .result
+        .take()
+        .expect("map callback was not called");
+
+    staging.collect(result)
+}
+
+#[cfg(test)]
+mod readback_tests {
+    use std::future::Future;
+
+    use crate::prelude::*;
+
+    #[tokio::test]
+    async fn test_storage_readback() {
+        let render_context = RenderContext::new(RenderContextConfig::default()).await;
+
+        let buffer = StorageBuffer::new_init(&render_context, None, &[0.0f32, 1.0, 2.0, 3.0]);
+
+        assert_eq!(buffer.read(&render_context).await, [0.0, 1.0, 2.0, 3.0]);
+        assert_eq!(
+            buffer.read_range_blocking(&render_context, 1..3),
+            [1.0, 2.0]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dynamic_readback() {
+        let render_context = RenderContext::new(RenderContextConfig::default()).await;
+
+        let mut buffer = DynamicBuffer::new_init(
+            &render_context,
+            None,
+            &[1u32, 2, 3],
+            std::num::NonZeroU64::new(8),
+        );
+
+        assert_eq!(buffer.read_blocking(&render_context), [1, 2, 3]);
+
+        buffer.write(&render_context, &[4, 5, 6, 7, 8]);
+
+        assert_eq!(buffer.read_range(&render_context, 1..4).await, [5, 6, 7]);
+    }
+
+    #[tokio::test]
+    async fn test_unaligned_readback() {
+        let render_context = RenderContext::new(RenderContextConfig::default()).await;
+
+        // 6 bytes of elements, allocated as 8 so that copies stay aligned
+        let buffer =
+            StorageBuffer::<u16>::new(&render_context, None, std::num::NonZeroU64::new(3).unwrap());
+        assert_eq!(unsafe { buffer.wgpu_buffer() }.size(), 8);
+
+        unsafe { render_context.queue() }.write_buffer(
+            unsafe { buffer.wgpu_buffer() },
+            0,
+            bytemuck::cast_slice(&[1u16, 2, 3, 0]),
+        );
+
+        assert_eq!(buffer.read(&render_context).await, [1, 2, 3]);
+        assert_eq!(buffer.read_range_blocking(&render_context, 2..3), [3]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn test_read_without_spinning() {
+        let render_context = RenderContext::new(RenderContextConfig::default()).await;
+
+        let buffer = StorageBuffer::new_init(&render_context, None, &[1u32, 2, 3, 4]);
+
+        let mut polls = 0;
+        let mut read = std::pin::pin!(buffer.read(&render_context));
+        let data = std::future::poll_fn(|cx| {
+            polls += 1;
+            read.as_mut().poll(cx)
+        })
+        .await;
+
+        assert_eq!(data, [1, 2, 3, 4]);
+        // once pending, only the map callback wakes the task again
+        assert!(polls <= 2, "polled {polls} times");
+    }
+}
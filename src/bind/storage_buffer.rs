@@ -1,4 +1,6 @@
-use std::{mem, num::NonZeroU64, slice};
+use std::{mem, num::NonZeroU64, ops::Range, slice};
+
+use super::readback;
 
 use crate::prelude::*;
 
@@ -11,13 +13,16 @@ pub struct StorageBuffer<T: 'static> {
 
 impl<T: 'static> StorageBuffer<T> {
     #[inline]
-    pub fn new(render_context: &RenderContext, size: NonZeroU64) -> Self {
+    pub fn new(render_context: &RenderContext, label: Option<&str>, size: NonZeroU64) -> Self {
         let device = unsafe { render_context.device() };
 
         let buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: None,
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
-            size: mem::size_of::<T>() as u64 * size.get(),
+            label,
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
+            size: (mem::size_of::<T>() as u64 * size.get())
+                .next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT),
             mapped_at_creation: false,
         });
 
@@ -29,24 +34,27 @@ impl<T: 'static> StorageBuffer<T> {
     }
 
     #[inline]
-    pub fn new_init(render_context: &RenderContext, value: &[T]) -> Self
+    pub fn new_init(render_context: &RenderContext, label: Option<&str>, value: &[T]) -> Self
     where
-        T: bytemuck::NoUninit,
+        T: StorageData,
     {
+        let () = T::CHECK;
+
         let size = NonZeroU64::new(value.len() as u64).expect("storage buffer size cannot be zero");
 
         let device = unsafe { render_context.device() };
 
         let buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: None,
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
-            size: mem::size_of::<T>() as u64 * size.get(),
+            label,
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
+            size: (mem::size_of::<T>() as u64 * size.get())
+                .next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT),
             mapped_at_creation: true,
         });
 
-        buffer
-            .slice(..std::mem::size_of_val(value) as u64)
-            .get_mapped_range_mut()
+        buffer.slice(..).get_mapped_range_mut()[..mem::size_of_val(value)]
             .copy_from_slice(bytemuck::cast_slice(value));
 
         buffer.unmap();
@@ -61,6 +69,7 @@ impl<T: 'static> StorageBuffer<T> {
     #[inline]
     pub fn with_usage(
         render_context: &RenderContext,
+        label: Option<&str>,
         size: NonZeroU64,
         usage: wgpu::BufferUsages,
         mapped_at_creation: bool,
@@ -68,9 +77,10 @@ impl<T: 'static> StorageBuffer<T> {
         let device = unsafe { render_context.device() };
 
         let buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: None,
+            label,
             usage,
-            size: mem::size_of::<T>() as u64 * size.get(),
+            size: (mem::size_of::<T>() as u64 * size.get())
+                .next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT),
             mapped_at_creation,
         });
 
@@ -83,26 +93,28 @@ impl<T: 'static> StorageBuffer<T> {
 
     pub fn with_usage_init(
         render_context: &RenderContext,
+        label: Option<&str>,
         value: &[T],
         usage: wgpu::BufferUsages,
     ) -> Self
     where
-        T: bytemuck::NoUninit,
+        T: StorageData,
     {
+        let () = T::CHECK;
+
         let size = NonZeroU64::new(value.len() as u64).expect("storage buffer size cannot be zero");
 
         let device = unsafe { render_context.device() };
 
         let buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: None,
+            label,
             usage: wgpu::BufferUsages::STORAGE | usage,
-            size: mem::size_of::<T>() as u64 * size.get(),
+            size: (mem::size_of::<T>() as u64 * size.get())
+                .next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT),
             mapped_at_creation: true,
         });
 
-        buffer
-            .slice(..std::mem::size_of_val(value) as u64)
-            .get_mapped_range_mut()
+        buffer.slice(..).get_mapped_range_mut()[..mem::size_of_val(value)]
             .copy_from_slice(bytemuck::cast_slice(value));
 
         buffer.unmap();
@@ -136,7 +148,7 @@ impl<T: 'static> StorageBuffer<T> {
     #[inline(always)]
     pub fn write(&self, render_context: &RenderContext, data: &[T])
     where
-        T: bytemuck::NoUninit,
+        T: StorageData,
     {
         self.write_at_offset(render_context, data, 0);
     }
@@ -144,7 +156,7 @@ impl<T: 'static> StorageBuffer<T> {
     #[inline]
     pub fn write_at_offset(&self, render_context: &RenderContext, data: &[T], offset: u64)
     where
-        T: bytemuck::NoUninit,
+        T: StorageData,
     {
         if offset + data.len() as u64 > self.size.get() {
             panic!(
@@ -167,8 +179,10 @@ impl<T: 'static> StorageBuffer<T> {
         data: &[T],
         offset: u64,
     ) where
-        T: bytemuck::NoUninit,
+        T: StorageData,
     {
+        let () = T::CHECK;
+
         let queue = unsafe { render_context.queue() };
 
         queue.write_buffer(
@@ -181,7 +195,7 @@ impl<T: 'static> StorageBuffer<T> {
     #[inline]
     pub fn set_at_offset(&self, render_context: &RenderContext, data: &T, offset: u64)
     where
-        T: bytemuck::NoUninit,
+        T: StorageData,
     {
         if offset >= self.size.get() {
             panic!(
@@ -203,8 +217,10 @@ impl<T: 'static> StorageBuffer<T> {
         data: &T,
         offset: u64,
     ) where
-        T: bytemuck::NoUninit,
+        T: StorageData,
     {
+        let () = T::CHECK;
+
         let queue = unsafe { render_context.queue() };
 
         let slice = unsafe { slice::from_raw_parts(data, 1) };
@@ -216,3 +232,46 @@ impl<T: 'static> StorageBuffer<T> {
         );
     }
 }
+
+/// Reading requires the buffer to have `BufferUsages::COPY_SRC`, which is
+/// included by `new` and `new_init` but must be given to `with_usage`
+impl<T: bytemuck::Pod> StorageBuffer<T> {
+    /// Copies the whole buffer into a staging buffer and reads it back
+    ///
+    /// The device is waited on from a helper thread, so awaiting this does
+    /// not keep the executor busy until the copy has finished
+    #[inline(always)]
+    pub async fn read(&self, render_context: &RenderContext) -> Vec<T> {
+        self.read_range(render_context, 0..self.size.get()).await
+    }
+
+    pub async fn read_range(&self, render_context: &RenderContext, range: Range<u64>) -> Vec<T> {
+        self.check_range(&range);
+
+        readback::read_buffer(render_context, &self.buffer, range).await
+    }
+
+    /// Like `read`, but blocks until the device has finished all submitted work
+    #[inline(always)]
+    pub fn read_blocking(&self, render_context: &RenderContext) -> Vec<T> {
+        self.read_range_blocking(render_context, 0..self.size.get())
+    }
+
+    pub fn read_range_blocking(&self, render_context: &RenderContext, range: Range<u64>) -> Vec<T> {
+        self.check_range(&range);
+
+        readback::read_buffer_blocking(render_context, &self.buffer, range)
+    }
+
+    #[inline]
+    fn check_range(&self, range: &Range<u64>) {
+        if range.start > range.end || range.end > self.size.get() {
+            panic!(
+                "range ({}..{}) out of bounds of buffer size ({})",
+                range.start,
+                range.end,
+                self.size.get()
+            );
+        }
+    }
+}
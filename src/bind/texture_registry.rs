@@ -0,0 +1,450 @@
+use std::num::NonZeroU32;
+
+use crate::prelude::*;
+
+/// Layout of a `TextureRegistry`, a `binding_array` of up to `CAPACITY` 2D
+/// float textures at binding 0 and the sampler they share at binding 1
+///
+/// ```wgsl
+/// @group(1) @binding(0)
+/// var textures: binding_array<texture_2d<f32>>;
+/// @group(1) @binding(1)
+/// var textures_sampler: sampler;
+/// ```
+#[repr(transparent)]
+#[derive(Debug, Clone)]
+pub struct TextureRegistryLayout<const CAPACITY: u32> {
+    layout: wgpu::BindGroupLayout,
+}
+
+impl<const CAPACITY: u32> TextureRegistryLayout<CAPACITY> {
+    pub const LABEL: &str = "TextureRegistryLayout";
+
+    /// Features the device must have been requested with, binding arrays
+    /// that are only partly filled and indexed by a per-material value
+    pub const FEATURES: wgpu::Features = wgpu::Features::TEXTURE_BINDING_ARRAY
+        .union(wgpu::Features::PARTIALLY_BOUND_BINDING_ARRAY)
+        .union(wgpu::Features::SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING);
+
+    const COUNT: NonZeroU32 = NonZeroU32::new(CAPACITY).expect("capacity cannot be zero");
+
+    /// Raises `limits` so that a registry of `CAPACITY` textures can be bound
+    #[inline]
+    pub fn limits(limits: wgpu::Limits) -> wgpu::Limits {
+        wgpu::Limits {
+            max_binding_array_elements_per_shader_stage: limits
+                .max_binding_array_elements_per_shader_stage
+                .max(CAPACITY),
+            ..limits
+        }
+    }
+
+    pub fn new(render_context: &RenderContext) -> Self {
+        let device = unsafe { render_context.device() };
+
+        let missing = Self::FEATURES.difference(device.features());
+        if !missing.is_empty() {
+            panic!("texture registry requires the device features {missing:?}");
+        }
+
+        Self {
+            layout: device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                entries: &Self::entries(),
+                label: Some(Self::LABEL),
+            }),
+        }
+    }
+
+    #[inline]
+    pub async fn new_checked(render_context: &RenderContext) -> Result<Self, ValidationError> {
+        render_context
+            .capture_validation(|| Self::new(render_context))
+            .await
+    }
+}
+
+impl<const CAPACITY: u32> BindLayoutEntries for TextureRegistryLayout<CAPACITY> {
+    fn entries() -> Vec<wgpu::BindGroupLayoutEntry> {
+        vec![
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: Some(Self::COUNT),
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            },
+        ]
+    }
+}
+
+impl<const CAPACITY: u32> BindLayout for TextureRegistryLayout<CAPACITY> {
+    #[inline(always)]
+    fn wgpu_layout(&self) -> &wgpu::BindGroupLayout {
+        &self.layout
+    }
+}
+
+/// A growable table of textures bound all at once, so that materials can
+/// pick their texture with an index instead of a bind group of their own
+///
+/// Indices stay valid until their texture is removed, after which they are
+/// handed out again. Removed slots are filled with a 1x1 placeholder so the
+/// array stays contiguous, and the bind group is rebuilt on every change
+///
+/// Being a `Bind`, it can be the `SharedData` of a `Layout`, whose
+/// `set_shared_data` sets its bind group once for every material drawn
+#[derive(Debug, Clone)]
+pub struct TextureRegistry<const CAPACITY: u32> {
+    bind_group: wgpu::BindGroup,
+    layout: TextureRegistryLayout<CAPACITY>,
+    sampler: Sampler,
+    placeholder: RawTexture<Texture2D>,
+    textures: Vec<Option<RawTexture<Texture2D>>>,
+    free: Vec<u32>,
+}
+
+impl<const CAPACITY: u32> TextureRegistry<CAPACITY> {
+    pub const LABEL: &str = "TextureRegistry";
+
+    pub fn new(
+        render_context: &RenderContext,
+        layout: TextureRegistryLayout<CAPACITY>,
+        sampler: Sampler,
+    ) -> Self {
+        let placeholder = RawTexture::new(
+            render_context,
+            wgpu::Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            &TextureConfig {
+                label: Some("TextureRegistry placeholder"),
+                ..default()
+            },
+        );
+
+        let bind_group =
+            Self::create_bind_group(render_context, &layout, &sampler, &[&placeholder]);
+
+        Self {
+            bind_group,
+            layout,
+            sampler,
+            placeholder,
+            textures: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    #[inline(always)]
+    pub const fn capacity(&self) -> u32 {
+        CAPACITY
+    }
+
+    /// Number of textures in the registry
+    #[inline]
+    pub fn len(&self) -> u32 {
+        (self.textures.len() - self.free.len()) as u32
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.len() == CAPACITY
+    }
+
+    #[inline]
+    pub fn get(&self, index: u32) -> Option<&RawTexture<Texture2D>> {
+        self.textures.get(index as usize)?.as_ref()
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &RawTexture<Texture2D>)> {
+        self.textures
+            .iter()
+            .enumerate()
+            .filter_map(|(index, texture)| Some((index as u32, texture.as_ref()?)))
+    }
+
+    /// Adds `texture` to the registry and returns the index to sample it
+    /// with, the lowest free one
+    ///
+    /// # Panics
+    /// Panics if the registry is full
+    pub fn insert(
+        &mut self,
+        render_context: &RenderContext,
+        texture: RawTexture<Texture2D>,
+    ) -> u32 {
+        let index = self.insert_texture(texture);
+        self.refresh(render_context);
+
+        index
+    }
+
+    /// Adds every texture and returns their indices, rebuilding the bind
+    /// group only once
+    ///
+    /// # Panics
+    /// Panics if the registry fills up
+    pub fn extend(
+        &mut self,
+        render_context: &RenderContext,
+        textures: impl IntoIterator<Item = RawTexture<Texture2D>>,
+    ) -> Vec<u32> {
+        let indices = textures
+            .into_iter()
+            .map(|texture| self.insert_texture(texture))
+            .collect();
+        self.refresh(render_context);
+
+        indices
+    }
+
+    /// Puts `texture` at `index`, which must have been handed out by the
+    /// registry and not removed since, returning the texture it replaces
+    pub fn replace(
+        &mut self,
+        render_context: &RenderContext,
+        index: u32,
+        texture: RawTexture<Texture2D>,
+    ) -> RawTexture<Texture2D> {
+        let Some(Some(slot)) = self.textures.get_mut(index as usize) else {
+            panic!("no texture at index ({index})");
+        };
+
+        let previous = std::mem::replace(slot, texture);
+        self.refresh(render_context);
+
+        previous
+    }
+
+    /// Removes the texture at `index`, leaving the placeholder in its place
+    /// until the index is handed out again
+    pub fn remove(
+        &mut self,
+        render_context: &RenderContext,
+        index: u32,
+    ) -> Option<RawTexture<Texture2D>> {
+        let texture = self.textures.get_mut(index as usize)?.take()?;
+
+        if index as usize == self.textures.len() - 1 {
+            self.textures.pop();
+
+            // trailing free slots are dropped rather than kept as placeholders
+            while let Some(None) = self.textures.last() {
+                self.textures.pop();
+            }
+            let len = self.textures.len() as u32;
+            self.free.retain(|&free| free < len);
+        } else {
+            self.free.push(index);
+        }
+
+        self.refresh(render_context);
+
+        Some(texture)
+    }
+
+    fn insert_texture(&mut self, texture: RawTexture<Texture2D>) -> u32 {
+        if self.is_full() {
+            panic!("texture registry is full ({CAPACITY} textures)");
+        }
+
+        // the lowest free index keeps the bound array as short as possible
+        let lowest = self
+            .free
+            .iter()
+            .enumerate()
+            .min_by_key(|&(_, &index)| index)
+            .map(|(position, _)| position);
+
+        match lowest {
+            Some(position) => {
+                let index = self.free.swap_remove(position);
+                self.textures[index as usize] = Some(texture);
+                index
+            }
+            None => {
+                self.textures.push(Some(texture));
+                self.textures.len() as u32 - 1
+            }
+        }
+    }
+
+    /// Rebuilds the bind group from the current textures, this is done by
+    /// every change to the registry so is only needed after a texture has
+    /// been resized
+    pub fn refresh(&mut self, render_context: &RenderContext) {
+        let mut textures: Vec<_> = self
+            .textures
+            .iter()
+            .map(|texture| texture.as_ref().unwrap_or(&self.placeholder))
+            .collect();
+
+        if textures.is_empty() {
+            textures.push(&self.placeholder);
+        }
+
+        self.bind_group =
+            Self::create_bind_group(render_context, &self.layout, &self.sampler, &textures);
+    }
+
+    fn create_bind_group(
+        render_context: &RenderContext,
+        layout: &TextureRegistryLayout<CAPACITY>,
+        sampler: &Sampler,
+        textures: &[&RawTexture<Texture2D>],
+    ) -> wgpu::BindGroup {
+        let views: Vec<_> = textures
+            .iter()
+            .map(|texture| unsafe { texture.view() }.inner())
+            .collect();
+
+        unsafe { render_context.device() }.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: layout.wgpu_layout(),
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureViewArray(&views),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(unsafe { sampler.inner() }),
+                },
+            ],
+            label: Some(Self::LABEL),
+        })
+    }
+
+    #[inline(always)]
+    pub const fn sampler(&self) -> &Sampler {
+        &self.sampler
+    }
+}
+
+impl<const CAPACITY: u32> std::ops::Deref for TextureRegistry<CAPACITY> {
+    type Target = TextureRegistryLayout<CAPACITY>;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.layout
+    }
+}
+
+impl<const CAPACITY: u32> Bind for TextureRegistry<CAPACITY> {
+    type Layout = TextureRegistryLayout<CAPACITY>;
+
+    #[inline(always)]
+    fn bind_group(&self) -> &wgpu::BindGroup {
+        &self.bind_group
+    }
+
+    #[inline]
+    fn layout(&self) -> &Self::Layout {
+        &self.layout
+    }
+}
+
+#[cfg(test)]
+mod texture_registry_tests {
+    use crate::prelude::*;
+
+    type Registry = TextureRegistry<4>;
+    type RegistryLayout = TextureRegistryLayout<4>;
+
+    fn texture(render_context: &RenderContext) -> RawTexture<Texture2D> {
+        RawTexture::new(
+            render_context,
+            wgpu::Extent3d {
+                width: 2,
+                height: 2,
+                depth_or_array_layers: 1,
+            },
+            &TextureConfig::default(),
+        )
+    }
+
+    #[test]
+    fn test_texture_registry_reflection() {
+        let reflection = ShaderReflection::from_wgsl(
+            "
+            @group(1) @binding(0)
+            var textures: binding_array<texture_2d<f32>>;
+            @group(1) @binding(1)
+            var textures_sampler: sampler;
+
+            struct Material {
+                texture: u32,
+            };
+
+            @group(0) @binding(0)
+            var<uniform> material: Material;
+
+            @fragment
+            fn fs(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+                return textureSample(textures[material.texture], textures_sampler, uv);
+            }
+            ",
+        )
+        .unwrap();
+
+        reflection
+            .validate_bind_layout::<RegistryLayout>(1)
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_texture_registry_indices() {
+        let render_context = RenderContext::new(RenderContextConfig {
+            features: Some(RegistryLayout::FEATURES),
+            limits: Some(RegistryLayout::limits(wgpu::Limits::default())),
+            ..default()
+        })
+        .await;
+
+        let layout = RegistryLayout::new_checked(&render_context).await.unwrap();
+        let sampler = Sampler::new(&render_context, &wgpu::SamplerDescriptor::default());
+
+        let mut registry = Registry::new(&render_context, layout, sampler);
+
+        let indices = registry.extend(&render_context, (0..3).map(|_| texture(&render_context)));
+        assert_eq!(indices, [0, 1, 2]);
+
+        assert!(registry.remove(&render_context, 1).is_some());
+        assert!(registry.get(1).is_none());
+        assert!(registry.get(2).is_some());
+
+        assert_eq!(
+            registry.insert(&render_context, texture(&render_context)),
+            1
+        );
+        assert_eq!(
+            registry.insert(&render_context, texture(&render_context)),
+            3
+        );
+        assert!(registry.is_full());
+
+        assert!(registry.remove(&render_context, 3).is_some());
+        assert!(registry.remove(&render_context, 2).is_some());
+        assert_eq!(registry.len(), 2);
+        assert_eq!(
+            registry.insert(&render_context, texture(&render_context)),
+            2
+        );
+    }
+}
@@ -11,11 +11,11 @@ pub struct UniformBuffer<T: ?Sized + 'static> {
 
 impl<T: 'static> UniformBuffer<T> {
     #[inline]
-    pub fn new(render_context: &RenderContext) -> Self {
+    pub fn new(render_context: &RenderContext, label: Option<&str>) -> Self {
         let device = unsafe { render_context.device() };
 
         let buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: None,
+            label,
             usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
             size: size_of::<T>() as u64,
             mapped_at_creation: false,
@@ -30,13 +30,14 @@ impl<T: 'static> UniformBuffer<T> {
     #[inline]
     pub fn with_usage(
         render_context: &RenderContext,
+        label: Option<&str>,
         usage: wgpu::BufferUsages,
         mapped_at_creation: bool,
     ) -> Self {
         let device = unsafe { render_context.device() };
 
         let buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: None,
+            label,
             usage: wgpu::BufferUsages::UNIFORM | usage,
             size: size_of::<T>() as u64,
             mapped_at_creation,
@@ -63,13 +64,15 @@ impl<T: 'static> UniformBuffer<T> {
     }
 }
 
-impl<T: 'static + bytemuck::NoUninit> UniformBuffer<T> {
+impl<T: 'static + UniformData> UniformBuffer<T> {
     #[inline]
-    pub fn new_init(render_context: &RenderContext, value: &T) -> Self {
+    pub fn new_init(render_context: &RenderContext, label: Option<&str>, value: &T) -> Self {
+        let () = T::CHECK;
+
         let device = unsafe { render_context.device() };
 
         let buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: None,
+            label,
             size: size_of::<T>() as u64,
             usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
             mapped_at_creation: true,
@@ -91,13 +94,16 @@ impl<T: 'static + bytemuck::NoUninit> UniformBuffer<T> {
     #[inline]
     pub fn init_with_usage(
         render_context: &RenderContext,
+        label: Option<&str>,
         usage: wgpu::BufferUsages,
         value: &T,
     ) -> Self {
+        let () = T::CHECK;
+
         let device = unsafe { render_context.device() };
 
         let buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: None,
+            label,
             usage: wgpu::BufferUsages::UNIFORM | usage,
             size: size_of::<T>() as u64,
             mapped_at_creation: true,
@@ -118,6 +124,8 @@ impl<T: 'static + bytemuck::NoUninit> UniformBuffer<T> {
 
     #[inline]
     pub fn write(&self, render_context: &RenderContext, value: &T) {
+        let () = T::CHECK;
+
         let queue = unsafe { render_context.queue() };
 
         queue.write_buffer(
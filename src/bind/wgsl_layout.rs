@@ -0,0 +1,401 @@
+/// Describes how a type is laid out in WGSL's storage address space (std430)
+///
+/// # Safety
+/// `SIZE` must equal `size_of::<Self>()` and every field of `Self` must be at
+/// the offset WGSL expects, `create_wgsl_layout::wgsl_layout!` checks this at
+/// compile time for existing structs and `create_wgsl_layout::wgsl_struct!`
+/// defines structs with the padding already inserted
+pub unsafe trait WgslLayout: bytemuck::NoUninit {
+    /// `AlignOf` in the storage address space
+    const ALIGN: usize;
+    /// `SizeOf`, which is the same in every address space
+    const SIZE: usize;
+    /// `AlignOf` in the uniform address space, which rounds structs up to 16
+    const UNIFORM_ALIGN: usize = Self::ALIGN;
+    /// Space a member of this type must leave before the next member in the
+    /// uniform address space, which rounds structs up to 16
+    const UNIFORM_SIZE: usize = Self::SIZE;
+    /// Whether this type can be used in the uniform address space at all
+    const UNIFORM: bool = true;
+}
+
+/// Types that can be written into a `UniformBuffer`
+///
+/// With the `strict_layout` feature this requires `WgslLayout` and fails to
+/// compile when the type breaks the uniform address space layout rules
+pub trait UniformData: bytemuck::NoUninit {
+    #[doc(hidden)]
+    const CHECK: () = ();
+}
+
+/// Types that can be written into a `StorageBuffer` or a `DynamicBuffer`
+///
+/// With the `strict_layout` feature this requires `WgslLayout` and fails to
+/// compile when an array of the type would have a different stride in WGSL
+pub trait StorageData: bytemuck::NoUninit {
+    #[doc(hidden)]
+    const CHECK: () = ();
+}
+
+#[cfg(not(feature = "strict_layout"))]
+impl<T: bytemuck::NoUninit> UniformData for T {}
+
+#[cfg(not(feature = "strict_layout"))]
+impl<T: bytemuck::NoUninit> StorageData for T {}
+
+#[cfg(feature = "strict_layout")]
+impl<T: WgslLayout> UniformData for T {
+    const CHECK: () = assert!(
+        T::UNIFORM && T::SIZE == size_of::<T>(),
+        "type does not match the WGSL uniform address space layout"
+    );
+}
+
+#[cfg(feature = "strict_layout")]
+impl<T: WgslLayout> StorageData for T {
+    const CHECK: () = assert!(
+        T::SIZE == size_of::<T>() && T::SIZE % T::ALIGN == 0,
+        "type does not match the WGSL storage address space layout"
+    );
+}
+
+macro_rules! scalar_impl {
+    ($($T:ty),*) => {$(
+        unsafe impl WgslLayout for $T {
+            const ALIGN: usize = 4;
+            const SIZE: usize = 4;
+        }
+
+        unsafe impl WgslLayout for [$T; 2] {
+            const ALIGN: usize = 8;
+            const SIZE: usize = 8;
+        }
+
+        unsafe impl WgslLayout for [$T; 3] {
+            const ALIGN: usize = 16;
+            const SIZE: usize = 12;
+        }
+
+        unsafe impl WgslLayout for [$T; 4] {
+            const ALIGN: usize = 16;
+            const SIZE: usize = 16;
+        }
+    )*};
+}
+
+scalar_impl!(f32, i32, u32);
+
+/// `matCxR<f32>` as `[[f32; R]; C]`, only the layouts without padding
+/// between columns can be represented so `R` must be 2 or 4
+macro_rules! matrix_impl {
+    ($($C:literal x $R:literal),*) => {$(
+        unsafe impl WgslLayout for [[f32; $R]; $C] {
+            const ALIGN: usize = <[f32; $R] as WgslLayout>::ALIGN;
+            const SIZE: usize = $C * <[f32; $R] as WgslLayout>::ALIGN;
+        }
+    )*};
+}
+
+matrix_impl!(2 x 2, 3 x 2, 4 x 2, 2 x 4, 3 x 4, 4 x 4);
+
+pub mod create_wgsl_layout {
+    /// `(ALIGN, SIZE)` of a field, as passed to the layout helpers
+    pub type Field = (usize, usize);
+
+    #[inline(always)]
+    pub const fn round_up(align: usize, n: usize) -> usize {
+        n.div_ceil(align) * align
+    }
+
+    pub const fn align(fields: &[Field]) -> usize {
+        let mut align = 1;
+        let mut i = 0;
+
+        while i < fields.len() {
+            if fields[i].0 > align {
+                align = fields[i].0;
+            }
+            i += 1;
+        }
+
+        align
+    }
+
+    /// WGSL offset of the field at `index`, or of the end of the last
+    /// field when `index` is the number of fields
+    pub const fn offset(fields: &[Field], index: usize) -> usize {
+        let mut offset = 0;
+        let mut i = 0;
+
+        while i < index {
+            offset = round_up(fields[i].0, offset) + fields[i].1;
+            i += 1;
+        }
+
+        if index < fields.len() {
+            round_up(fields[index].0, offset)
+        } else {
+            offset
+        }
+    }
+
+    pub const fn size(fields: &[Field]) -> usize {
+        round_up(align(fields), offset(fields, fields.len()))
+    }
+
+    /// Bytes of padding to insert after `fields` so that a field of `align`
+    /// starts at its WGSL offset
+    pub const fn padding(fields: &[Field], align: usize) -> usize {
+        let end = offset(fields, fields.len());
+        round_up(align, end) - end
+    }
+
+    /// Whether the fields also satisfy the uniform address space rules,
+    /// given as `(UNIFORM_ALIGN, UNIFORM_SIZE, UNIFORM)` alongside `fields`
+    pub const fn uniform(fields: &[Field], uniform: &[(usize, usize, bool)]) -> bool {
+        let mut next = 0;
+        let mut i = 0;
+
+        while i < fields.len() {
+            let offset = offset(fields, i);
+            let (align, size, valid) = uniform[i];
+
+            if !valid || !offset.is_multiple_of(align) || offset < next {
+                return false;
+            }
+
+            next = offset + size;
+            i += 1;
+        }
+
+        true
+    }
+
+    #[doc(hidden)]
+    pub const fn assert_pod<T: bytemuck::Pod>() {}
+
+    /// Implements `WgslLayout` for an existing `#[repr(C)]` struct, failing
+    /// to compile if any field is not at its WGSL offset
+    #[allow(unused)]
+    #[macro_export]
+    macro_rules! wgsl_layout {
+        ($T:ty { $($field:ident: $F:ty),*$(,)? }) => {
+            unsafe impl $crate::prelude::WgslLayout for $T {
+                const ALIGN: usize = create_wgsl_layout::wgsl_layout!(@align $($F),*);
+                const SIZE: usize = create_wgsl_layout::size(
+                    &[$((
+                        <$F as $crate::prelude::WgslLayout>::ALIGN,
+                        <$F as $crate::prelude::WgslLayout>::SIZE,
+                    )),*]
+                );
+                const UNIFORM_ALIGN: usize = create_wgsl_layout::round_up(16, Self::ALIGN);
+                const UNIFORM_SIZE: usize = create_wgsl_layout::round_up(16, Self::SIZE);
+                const UNIFORM: bool = create_wgsl_layout::uniform(
+                    &[$((
+                        <$F as $crate::prelude::WgslLayout>::ALIGN,
+                        <$F as $crate::prelude::WgslLayout>::SIZE,
+                    )),*],
+                    &[$((
+                        <$F as $crate::prelude::WgslLayout>::UNIFORM_ALIGN,
+                        <$F as $crate::prelude::WgslLayout>::UNIFORM_SIZE,
+                        <$F as $crate::prelude::WgslLayout>::UNIFORM,
+                    )),*],
+                );
+            }
+
+            const _: () = {
+                let fields = [$((
+                    <$F as $crate::prelude::WgslLayout>::ALIGN,
+                    <$F as $crate::prelude::WgslLayout>::SIZE,
+                )),*];
+                let mut index = 0;
+
+                $(
+                    assert!(
+                        create_wgsl_layout::offset(&fields, index)
+                            == std::mem::offset_of!($T, $field),
+                        concat!(
+                            "field `", stringify!($field), "` of `", stringify!($T),
+                            "` is not at its WGSL offset"
+                        )
+                    );
+                    index += 1;
+                )*
+
+                let _ = index;
+
+                assert!(
+                    <$T as $crate::prelude::WgslLayout>::SIZE == std::mem::size_of::<$T>(),
+                    concat!("`", stringify!($T), "` is not the same size as in WGSL")
+                );
+            };
+        };
+
+        (@align $($F:ty),*) => {
+            create_wgsl_layout::align(&[$((<$F as $crate::prelude::WgslLayout>::ALIGN, 0)),*])
+        };
+    }
+
+    /// Defines a `#[repr(C)]` struct with padding inserted wherever WGSL
+    /// expects it, implementing `WgslLayout`, `Pod` and `Zeroable`
+    ///
+    /// Padding fields are private, so the struct is built with `new` or
+    /// `bytemuck::Zeroable::zeroed` rather than a struct literal
+    #[allow(unused)]
+    #[macro_export]
+    macro_rules! wgsl_struct {
+        ($(#[$meta:meta])* $vis:vis struct $T:ident {
+            $($fvis:vis $field:ident: $F:ty),*$(,)?
+        }) => {
+            create_wgsl_layout::wgsl_struct!(@munch
+                [$(#[$meta])* $vis struct $T]
+                [
+                    __pad0 __pad1 __pad2 __pad3 __pad4 __pad5 __pad6 __pad7
+                    __pad8 __pad9 __pad10 __pad11 __pad12 __pad13 __pad14 __pad15
+                    __pad16 __pad17 __pad18 __pad19 __pad20 __pad21 __pad22 __pad23
+                    __pad24 __pad25 __pad26 __pad27 __pad28 __pad29 __pad30 __pad31
+                ]
+                []
+                []
+                $($fvis $field: $F,)*
+            );
+
+            #[allow(unused)]
+            impl $T {
+                #[inline]
+                #[allow(clippy::too_many_arguments)]
+                $vis fn new($($field: $F),*) -> Self {
+                    Self {
+                        $($field,)*
+                        ..$crate::bytemuck::Zeroable::zeroed()
+                    }
+                }
+            }
+
+            create_wgsl_layout::wgsl_layout!($T { $($field: $F),* });
+        };
+
+        (@munch
+            [$($head:tt)*]
+            [$pad:ident $($pads:ident)*]
+            [$($fields:tt)*]
+            [$($P:ty,)*]
+            $fvis:vis $field:ident: $F:ty,
+            $($rest:tt)*
+        ) => {
+            create_wgsl_layout::wgsl_struct!(@munch
+                [$($head)*]
+                [$($pads)*]
+                [
+                    $($fields)*
+                    #[allow(dead_code)]
+                    $pad: [u8; create_wgsl_layout::padding(
+                        &[$((
+                            <$P as $crate::prelude::WgslLayout>::ALIGN,
+                            <$P as $crate::prelude::WgslLayout>::SIZE,
+                        )),*],
+                        <$F as $crate::prelude::WgslLayout>::ALIGN,
+                    )],
+                    $fvis $field: $F,
+                ]
+                [$($P,)* $F,]
+                $($rest)*
+            );
+        };
+
+        (@munch
+            [$(#[$meta:meta])* $vis:vis struct $T:ident]
+            [$($pads:ident)*]
+            [$($fields:tt)*]
+            [$($P:ty,)*]
+        ) => {
+            $(#[$meta])*
+            #[repr(C)]
+            #[derive(Clone, Copy)]
+            $vis struct $T {
+                $($fields)*
+                #[allow(dead_code)]
+                __pad_end: [u8; create_wgsl_layout::padding(
+                    &[$((
+                        <$P as $crate::prelude::WgslLayout>::ALIGN,
+                        <$P as $crate::prelude::WgslLayout>::SIZE,
+                    )),*],
+                    create_wgsl_layout::wgsl_layout!(@align $($P),*),
+                )],
+            }
+
+            const _: () = {
+                $(create_wgsl_layout::assert_pod::<$P>();)*
+            };
+
+            // SAFETY: every field is `Pod` and the padding fields leave no
+            // implicit padding, which `wgsl_layout!` checks through the size
+            unsafe impl $crate::bytemuck::Zeroable for $T {}
+            unsafe impl $crate::bytemuck::Pod for $T {}
+        };
+    }
+
+    pub use wgsl_layout;
+    pub use wgsl_struct;
+}
+
+#[cfg(test)]
+mod wgsl_layout_tests {
+    use crate::prelude::*;
+
+    create_wgsl_layout::wgsl_struct!(
+        #[derive(Debug)]
+        struct Light {
+            position: [f32; 3],
+            intensity: f32,
+            color: [f32; 3],
+            range: [f32; 2],
+        }
+    );
+
+    create_wgsl_layout::wgsl_struct!(
+        struct Lights {
+            ambient: [f32; 4],
+            light: Light,
+            count: u32,
+        }
+    );
+
+    #[test]
+    fn test_padding() {
+        assert_eq!(std::mem::offset_of!(Light, intensity), 12);
+        assert_eq!(std::mem::offset_of!(Light, color), 16);
+        assert_eq!(std::mem::offset_of!(Light, range), 32);
+        assert_eq!(<Light as WgslLayout>::ALIGN, 16);
+        assert_eq!(size_of::<Light>(), 48);
+
+        assert_eq!(std::mem::offset_of!(Lights, light), 16);
+        assert_eq!(std::mem::offset_of!(Lights, count), 64);
+        assert_eq!(size_of::<Lights>(), 80);
+        const { assert!(<Lights as WgslLayout>::UNIFORM) };
+
+        let light = Light::new([1.0; 3], 2.0, [3.0; 3], [4.0; 2]);
+        assert_eq!(light.intensity, 2.0);
+    }
+
+    #[test]
+    fn test_uniform_rules() {
+        create_wgsl_layout::wgsl_struct!(
+            struct Scalar {
+                value: f32,
+            }
+        );
+
+        create_wgsl_layout::wgsl_struct!(
+            struct Packed {
+                scalar: Scalar,
+                next: f32,
+            }
+        );
+
+        // a struct member must be 16-byte aligned and leave 16 bytes
+        // before the next member in the uniform address space
+        const { assert!(!<Packed as WgslLayout>::UNIFORM) };
+        assert_eq!(size_of::<Packed>(), 8);
+    }
+}
@@ -5,4 +5,5 @@ pub mod hdr;
 pub mod post_processing;
 pub mod tileset;
 pub mod transform;
+pub mod transparent_queue;
 pub mod vertex;
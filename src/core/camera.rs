@@ -2,7 +2,7 @@ use std::f32::consts::PI;
 
 use crate::prelude::{core::*, *};
 
-use cgmath::{Matrix4, Rad};
+use cgmath::{InnerSpace, Matrix4, Rad, Vector3};
 
 create_bind::bind!(CameraBind, CameraBindLayout {
     UniformBuffers => {
@@ -22,6 +22,7 @@ pub const OPENGL_TO_WGPU_MATRIX: cgmath::Matrix4<f32> = cgmath::Matrix4::new(
 pub struct Camera {
     pub projection: Projection,
     projection_matrix: ProjectionMatrix,
+    transform: Transform,
     uniform: CameraUniform,
     bind: CameraBind,
 }
@@ -34,6 +35,7 @@ impl Camera {
         Self {
             uniform: projection_matrix.apply_transform(&transform),
             projection_matrix,
+            transform,
             projection,
             bind,
         }
@@ -48,9 +50,24 @@ impl Camera {
     #[inline]
     pub fn apply_transform(&mut self, transform: Transform) -> &mut Self {
         self.uniform = self.projection_matrix.apply_transform(&transform);
+        self.transform = transform;
         self
     }
 
+    /// The view transform last applied to the camera
+    #[inline(always)]
+    pub const fn transform(&self) -> Transform {
+        self.transform
+    }
+
+    /// Distance from the camera to a point in world space
+    #[inline]
+    pub fn distance(&self, position: Vector3<f32>) -> f32 {
+        (self.transform.transform_matrix() * position.extend(1.0))
+            .truncate()
+            .magnitude()
+    }
+
     #[inline(always)]
     pub const fn projection_matrix(&self) -> ProjectionMatrix {
         self.projection_matrix
@@ -146,6 +163,8 @@ pub struct CameraUniform {
     pub matrix: M4,
 }
 
+create_wgsl_layout::wgsl_layout!(CameraUniform { matrix: M4 });
+
 impl CameraUniform {
     pub const fn matrix(&self) -> &M4 {
         &self.matrix
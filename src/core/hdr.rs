@@ -21,6 +21,7 @@ impl Hdr {
                     depth_or_array_layers: 1,
                 },
                 &TextureConfig {
+                    label: Some("Hdr Texture"),
                     usages: Some(
                         wgpu::TextureUsages::RENDER_ATTACHMENT
                             | wgpu::TextureUsages::TEXTURE_BINDING,
@@ -32,6 +33,7 @@ impl Hdr {
             Sampler::new(
                 render_context,
                 &wgpu::SamplerDescriptor {
+                    label: Some("Hdr Sampler"),
                     mag_filter: wgpu::FilterMode::Nearest,
                     ..default()
                 },
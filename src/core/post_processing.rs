@@ -38,6 +38,7 @@ impl PostProc {
                     depth_or_array_layers: 1,
                 },
                 &TextureConfig {
+                    label: Some(PostProcBind::RENDER_LABEL),
                     usages: Some(
                         wgpu::TextureUsages::RENDER_ATTACHMENT
                             | wgpu::TextureUsages::TEXTURE_BINDING
@@ -46,7 +47,13 @@ impl PostProc {
                     ..default()
                 },
             ),
-            Sampler::new(render_context, &wgpu::SamplerDescriptor::default()),
+            Sampler::new(
+                render_context,
+                &wgpu::SamplerDescriptor {
+                    label: Some(PostProcBind::SAMPLER_LABEL),
+                    ..default()
+                },
+            ),
         );
 
         Self {
@@ -80,6 +87,8 @@ impl PostProc {
         Texture::new(self.raw_texture().clone(), self.sampler().clone())
     }
 
+    /// Recreates the texture at the new size, the bind group is rebuilt
+    /// by the next `refresh_if_dirty`
     pub fn resize(&mut self, render_context: &RenderContext, width: u32, height: u32) {
         self.bind.render.resize(
             render_context,
@@ -90,6 +99,7 @@ impl PostProc {
                 depth_or_array_layers: 1,
             },
         );
+        self.bind.mark_dirty();
     }
 }
 
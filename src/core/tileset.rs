@@ -30,7 +30,12 @@ pub struct TilesetQuad(Asc<SimpleMesh<PosVertex2d, index_format::Uint16>>);
 impl TilesetQuad {
     #[inline]
     pub fn new(render_context: &RenderContext) -> Self {
-        let mesh = SimpleMesh::new_uint16(render_context, QUAD_VERTICES, QUAD_INDICES);
+        let mesh = SimpleMesh::new_uint16(
+            render_context,
+            Some("Tileset Quad"),
+            QUAD_VERTICES,
+            QUAD_INDICES,
+        );
 
         Self(Asc::new(mesh))
     }
@@ -0,0 +1,236 @@
+use cgmath::Vector3;
+
+use crate::{
+    prelude::{core::*, *},
+    render_context::render_pass::NoInstance,
+};
+
+/// Meshes drawn with a transparent `BlendMode`, collected over a frame and
+/// drawn back-to-front from the camera so that each one blends over
+/// everything behind it
+#[derive(Debug)]
+pub struct TransparentQueue<'m, M: ?Sized> {
+    entries: Vec<(f32, &'m M)>,
+}
+
+impl<M: ?Sized> Default for TransparentQueue<'_, M> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'m, M: ?Sized> TransparentQueue<'m, M> {
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Queues `mesh`, placed at `position` in world space
+    #[inline]
+    pub fn push(&mut self, camera: &Camera, position: Vector3<f32>, mesh: &'m M) {
+        self.push_with_distance(camera.distance(position), mesh);
+    }
+
+    /// Queues `mesh` with an already known distance from the camera
+    #[inline]
+    pub fn push_with_distance(&mut self, distance: f32, mesh: &'m M) {
+        self.entries.push((distance, mesh));
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Orders the queue from the furthest mesh to the nearest
+    #[inline]
+    pub fn sort(&mut self) {
+        self.entries.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+    }
+
+    /// Meshes in the order they are queued, or back-to-front once sorted,
+    /// for drawing them with per-mesh state
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &'m M> + '_ {
+        self.entries.iter().map(|&(_, mesh)| mesh)
+    }
+
+    /// Sorts the queue and draws every mesh back-to-front
+    pub fn draw<L: Layout, S: Shader>(
+        &mut self,
+        render_pass: &mut RenderPass<'_, L, S, true, NoInstance>,
+    ) where
+        M: Mesh<VRequirements<L::VertexLayout>> + Sized,
+    {
+        self.sort();
+
+        for mesh in self.iter() {
+            render_pass.draw_mesh(mesh);
+        }
+    }
+}
+
+#[cfg(test)]
+mod transparent_queue_tests {
+    use std::{cell::RefCell, ops::Range};
+
+    use cgmath::{Deg, Quaternion, Rotation3, Vector3};
+
+    use crate::prelude::{core::*, *};
+
+    /// Full screen triangle that records when it is drawn
+    #[derive(Debug)]
+    struct Layer<'d> {
+        name: &'static str,
+        drawn: &'d RefCell<Vec<&'static str>>,
+    }
+
+    impl Mesh<()> for Layer<'_> {
+        unsafe fn draw(&self, render_pass: &mut wgpu::RenderPass) {
+            self.drawn.borrow_mut().push(self.name);
+            render_pass.draw(0..3, 0..1);
+        }
+
+        unsafe fn draw_instanced(&self, render_pass: &mut wgpu::RenderPass, instances: Range<u32>) {
+            self.drawn.borrow_mut().push(self.name);
+            render_pass.draw(0..3, instances);
+        }
+    }
+
+    struct LayerLayout {
+        layout: RawLayout<Void>,
+    }
+
+    impl Layout for LayerLayout {
+        type VertexLayout = Void;
+
+        fn raw_layout(&self) -> &RawLayout<Void> {
+            &self.layout
+        }
+    }
+
+    struct LayerShader {
+        pipeline: wgpu::RenderPipeline,
+    }
+
+    impl Shader for LayerShader {
+        type Layout = LayerLayout;
+
+        fn get_pipeline(&self) -> &wgpu::RenderPipeline {
+            &self.pipeline
+        }
+    }
+
+    const LAYER_SHADER: &str = "
+        @vertex
+        fn vs(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+            let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+            return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+        }
+
+        @fragment
+        fn fs() -> @location(0) vec4<f32> {
+            return vec4<f32>(1.0, 1.0, 1.0, 0.5);
+        }
+    ";
+
+    #[test]
+    fn test_back_to_front() {
+        let mut queue = TransparentQueue::<str>::new();
+
+        queue.push_with_distance(1.0, "near");
+        queue.push_with_distance(10.0, "far");
+        queue.push_with_distance(5.0, "middle");
+
+        queue.sort();
+
+        assert_eq!(queue.iter().collect::<Vec<_>>(), ["far", "middle", "near"]);
+    }
+
+    #[tokio::test]
+    async fn test_draw_from_camera() {
+        let render_context = RenderContext::new(RenderContextConfig::default()).await;
+
+        // the camera sits at z = 10 looking down the z axis, turned
+        // around it which leaves every distance unchanged
+        let camera = Camera::new(
+            CameraBind::new(
+                &render_context,
+                CameraBindLayout::new(&render_context),
+                UniformBuffer::new(&render_context, None),
+            ),
+            Projection::new(4.0, 4.0),
+            Transform {
+                position: Vector3::new(0.0, 0.0, -10.0),
+                rotation: Quaternion::from_angle_z(Deg(90.0)),
+                scale: 1.0,
+            },
+        );
+
+        assert!((camera.distance(Vector3::new(0.0, 0.0, 9.0)) - 1.0).abs() < 1e-4);
+        assert!((camera.distance(Vector3::new(0.0, 0.0, -5.0)) - 15.0).abs() < 1e-4);
+
+        let layout = LayerLayout {
+            layout: RawLayout::new(&render_context, default()),
+        };
+
+        let module = render_context
+            .create_shader_module(None, wgpu::ShaderSource::Wgsl(LAYER_SHADER.into()));
+
+        let shader = LayerShader {
+            pipeline: layout.create_pipeline(
+                &render_context,
+                &module,
+                ShaderConfig {
+                    depth_stencil: Some(None),
+                    blend: Some(BlendMode::Alpha),
+                    ..default()
+                },
+            ),
+        };
+
+        let drawn = RefCell::new(Vec::new());
+        let layers = ["near", "far", "middle"].map(|name| Layer {
+            name,
+            drawn: &drawn,
+        });
+
+        let mut queue = TransparentQueue::new();
+        queue.push(&camera, Vector3::new(0.0, 0.0, 9.0), &layers[0]);
+        queue.push(&camera, Vector3::new(0.0, 0.0, -5.0), &layers[1]);
+        queue.push(&camera, Vector3::new(0.0, 0.0, 0.0), &layers[2]);
+
+        let target = RenderTarget::new(&render_context, 4, 4, &default());
+
+        let mut encoder = render_context.command_encoder();
+        let mut render_pass = encoder
+            .render_target_pass(&target, None, false)
+            .create_shared_data::<LayerLayout>()
+            .apply_shader_with_default(&shader);
+        queue.draw(&mut render_pass);
+        drop(render_pass);
+        encoder.submit();
+
+        assert_eq!(*drawn.borrow(), ["far", "middle", "near"]);
+    }
+}
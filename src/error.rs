@@ -0,0 +1,190 @@
+use std::fmt::{Debug, Display};
+
+use crate::prelude::{BindingMismatch, ShaderLocation};
+
+#[derive(Debug)]
+pub enum InitError {
+    NoAdapter(wgpu::RequestAdapterError),
+    RequestDevice {
+        error: wgpu::RequestDeviceError,
+        features: wgpu::Features,
+        limits: Box<wgpu::Limits>,
+    },
+    CreateSurface(wgpu::CreateSurfaceError),
+    CreateWindow(winit::error::OsError),
+    EventLoop(winit::error::EventLoopError),
+}
+
+impl Display for InitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InitError::NoAdapter(error) => write!(f, "no suitable adapter found: {error}"),
+            InitError::RequestDevice {
+                error,
+                features,
+                limits,
+            } => write!(
+                f,
+                "failed to request device with features {features:?} and limits {limits:?}: {error}"
+            ),
+            InitError::CreateSurface(error) => write!(f, "failed to create surface: {error}"),
+            InitError::CreateWindow(error) => write!(f, "failed to create window: {error}"),
+            InitError::EventLoop(error) => write!(f, "event loop error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for InitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InitError::NoAdapter(error) => Some(error),
+            InitError::RequestDevice { error, .. } => Some(error),
+            InitError::CreateSurface(error) => Some(error),
+            InitError::CreateWindow(error) => Some(error),
+            InitError::EventLoop(error) => Some(error),
+        }
+    }
+}
+
+/// A validation error captured from wgpu with an error scope
+/// around the creation of a resource
+#[derive(Debug)]
+pub struct ValidationError {
+    pub error: wgpu::Error,
+}
+
+impl ValidationError {
+    pub fn message(&self) -> String {
+        match &self.error {
+            wgpu::Error::Validation { description, .. } => description.clone(),
+            error => error.to_string(),
+        }
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.error)
+    }
+}
+
+impl std::error::Error for ValidationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Every slot of a `DynamicUniformBuffer` has been pushed to since its last
+/// `clear`, so pushing another value would overwrite one that the pending
+/// submission may still read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapacityError {
+    pub capacity: u32,
+}
+
+impl Display for CapacityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "all {} slots of the dynamic uniform buffer are in use",
+            self.capacity
+        )
+    }
+}
+
+impl std::error::Error for CapacityError {}
+
+/// An error from reflecting a shader, or the bindings a layout
+/// does not provide as the shader declares them
+#[derive(Debug)]
+pub enum ReflectionError {
+    Parse(String),
+    Validation(String),
+    Mismatch(Vec<BindingMismatch>),
+}
+
+impl Display for ReflectionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReflectionError::Parse(error) => write!(f, "failed to parse shader: {error}"),
+            ReflectionError::Validation(error) => write!(f, "invalid shader: {error}"),
+            ReflectionError::Mismatch(mismatches) => {
+                write!(f, "layout does not match shader:")?;
+
+                for mismatch in mismatches {
+                    write!(f, "\n  {mismatch}")?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReflectionError {}
+
+/// An error from composing a shader, at the file and line it was written
+#[derive(Debug)]
+pub enum ComposeError {
+    Directive {
+        message: String,
+        location: ShaderLocation,
+    },
+    MissingInclude {
+        name: String,
+        location: ShaderLocation,
+    },
+    RecursiveInclude {
+        name: String,
+        location: ShaderLocation,
+    },
+    /// The composed shader failed to parse or validate, `location` is
+    /// `None` when naga does not point at a line
+    Shader {
+        message: String,
+        location: Option<ShaderLocation>,
+    },
+}
+
+impl ComposeError {
+    #[inline]
+    pub(crate) fn directive(message: impl Into<String>, location: ShaderLocation) -> Self {
+        ComposeError::Directive {
+            message: message.into(),
+            location,
+        }
+    }
+
+    pub fn location(&self) -> Option<&ShaderLocation> {
+        match self {
+            ComposeError::Directive { location, .. }
+            | ComposeError::MissingInclude { location, .. }
+            | ComposeError::RecursiveInclude { location, .. } => Some(location),
+            ComposeError::Shader { location, .. } => location.as_ref(),
+        }
+    }
+}
+
+impl Display for ComposeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ComposeError::Directive { message, location } => write!(f, "{location}: {message}"),
+            ComposeError::MissingInclude { name, location } => {
+                write!(f, "{location}: no snippet named \"{name}\" to include")
+            }
+            ComposeError::RecursiveInclude { name, location } => {
+                write!(f, "{location}: \"{name}\" includes itself")
+            }
+            ComposeError::Shader {
+                message,
+                location: Some(location),
+            } => write!(f, "{location}: {message}"),
+            ComposeError::Shader {
+                message,
+                location: None,
+            } => write!(f, "invalid shader: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for ComposeError {}
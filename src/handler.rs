@@ -10,6 +10,7 @@ use winit::{
 use crate::{handler::app::Framerate, prelude::*};
 
 pub mod app;
+pub mod frames_in_flight;
 pub mod window;
 
 pub trait OnStartCallback<C, S> {
@@ -76,6 +77,18 @@ impl<S> OnPollCallback<S> for Void {
 
 pub trait OnDrawCallback<S> {
     fn call(&mut self, app: &mut App<S>, event_loop: &ActiveEventLoop, window_id: WindowId);
+
+    /// Called after `call` for each time the surface was reconfigured or the
+    /// frame was skipped while acquiring it, see `WithSurfaceEvent` for closures
+    #[allow(unused)]
+    fn surface_event(
+        &mut self,
+        app: &mut App<S>,
+        event_loop: &ActiveEventLoop,
+        window_id: WindowId,
+        surface_event: SurfaceEvent,
+    ) {
+    }
 }
 
 impl<F: Fn(&mut App<S>, &ActiveEventLoop, WindowId), S> OnDrawCallback<S> for F {
@@ -90,6 +103,37 @@ impl<S> OnDrawCallback<S> for Void {
     fn call(&mut self, _: &mut App<S>, _: &ActiveEventLoop, _: WindowId) {}
 }
 
+/// An `OnDrawCallback` made of a draw closure and a closure receiving the
+/// surface events of the frames it draws
+#[derive(Debug, Clone, Copy)]
+pub struct WithSurfaceEvent<D, E> {
+    pub on_draw: D,
+    pub on_surface_event: E,
+}
+
+impl<
+        D: Fn(&mut App<S>, &ActiveEventLoop, WindowId),
+        E: Fn(&mut App<S>, &ActiveEventLoop, WindowId, SurfaceEvent),
+        S,
+    > OnDrawCallback<S> for WithSurfaceEvent<D, E>
+{
+    #[inline(always)]
+    fn call(&mut self, app: &mut App<S>, event_loop: &ActiveEventLoop, window_id: WindowId) {
+        (self.on_draw)(app, event_loop, window_id)
+    }
+
+    #[inline(always)]
+    fn surface_event(
+        &mut self,
+        app: &mut App<S>,
+        event_loop: &ActiveEventLoop,
+        window_id: WindowId,
+        surface_event: SurfaceEvent,
+    ) {
+        (self.on_surface_event)(app, event_loop, window_id, surface_event)
+    }
+}
+
 pub trait OnCloseCallBack<S> {
     fn call(self, app: &mut App<S>, event_loop: &ActiveEventLoop, window_id: WindowId);
 }
@@ -189,6 +233,9 @@ pub enum Handler<
         on_draw: OnDraw,
         on_close: Option<OnClose>,
     },
+    Failed {
+        error: Option<InitError>,
+    },
 }
 
 impl<
@@ -245,10 +292,16 @@ impl<
         }
     }
 
-    #[inline(always)]
-    pub fn run(&mut self, event_loop: EventLoop<()>) {
+    /// Runs the event loop until exit, returning any error that
+    /// prevented the window from being created
+    pub fn run(&mut self, event_loop: EventLoop<()>) -> Result<(), InitError> {
         event_loop.set_control_flow(ControlFlow::Poll);
-        event_loop.run_app(self).unwrap();
+        event_loop.run_app(self).map_err(InitError::EventLoop)?;
+
+        match self {
+            Handler::Failed { error } => error.take().map_or(Ok(()), Err),
+            _ => Ok(()),
+        }
     }
 }
 
@@ -275,13 +328,25 @@ impl<
             on_close,
         } = self
         {
-            let winit_window = Arc::new(
-                event_loop
-                    .create_window(window_config.window_attributes.take().unwrap_or_default())
-                    .unwrap(),
-            );
-
-            let mut window = Window::new(winit_window, render_context.clone(), window_config);
+            let window = event_loop
+                .create_window(window_config.window_attributes.take().unwrap_or_default())
+                .map_err(InitError::CreateWindow)
+                .and_then(|winit_window| {
+                    Window::try_new(
+                        Arc::new(winit_window),
+                        render_context.clone(),
+                        window_config,
+                    )
+                });
+
+            let mut window = match window {
+                Ok(window) => window,
+                Err(error) => {
+                    *self = Handler::Failed { error: Some(error) };
+                    event_loop.exit();
+                    return;
+                }
+            };
 
             let uninit_app = AppConfig {
                 render_context,
@@ -328,6 +393,8 @@ impl<
         match event {
             WindowEvent::CloseRequested => {
                 unsafe { on_close.take().unwrap_unchecked() }.call(app, event_loop, window_id);
+                // Failing to persist the cache only costs compile time on the next launch
+                let _ = app.render_context.save_pipeline_cache();
                 event_loop.exit();
                 return;
             }
@@ -338,6 +405,19 @@ impl<
             WindowEvent::RedrawRequested => {
                 *last_frame = Instant::now();
                 on_draw.call(app, event_loop, window_id);
+
+                let surface_events = app.window.take_surface_events();
+
+                if surface_events
+                    .iter()
+                    .any(|surface_event| matches!(surface_event, SurfaceEvent::Reconfigured(_)))
+                {
+                    app.window.reconfigure();
+                }
+
+                for surface_event in surface_events {
+                    on_draw.surface_event(app, event_loop, window_id, surface_event);
+                }
                 *poll_status = PollStatus::Polling;
             }
             _ => {}
@@ -40,6 +40,11 @@ impl<S> App<S> {
         }
     }
 
+    #[inline(always)]
+    pub fn try_command_encoder(&self) -> Result<WindowCommandEncoder<'_>, wgpu::SurfaceError> {
+        self.window.try_command_encoder()
+    }
+
     #[inline(always)]
     pub fn command_encoder(&self) -> WindowCommandEncoder<'_> {
         self.window.command_encoder()
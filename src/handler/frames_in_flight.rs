@@ -0,0 +1,146 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::prelude::*;
+
+/// One copy of `T` for every frame a `Window` can have in flight, so that a
+/// resource can be written for the next frame while the GPU is still reading
+/// it for an earlier one
+///
+/// The current copy moves on every `WindowCommandEncoder::present`, which
+/// makes this suited to buffers written through a mapping or large
+/// `DynamicBuffer`s, along with the binds that use them
+#[derive(Debug)]
+pub struct FramesInFlight<T> {
+    frames: Box<[T]>,
+    frame: Arc<AtomicU64>,
+}
+
+impl<T> FramesInFlight<T> {
+    /// Creates `Window::frames_in_flight` copies, passing the index of each
+    #[inline]
+    pub fn new(window: &Window, create: impl FnMut(usize) -> T) -> Self {
+        Self::from_counter(
+            window.frame_counter().clone(),
+            window.frames_in_flight(),
+            create,
+        )
+    }
+
+    /// Creates `count` copies which move on whenever `frame` is incremented
+    pub fn from_counter(
+        frame: Arc<AtomicU64>,
+        count: usize,
+        create: impl FnMut(usize) -> T,
+    ) -> Self {
+        Self {
+            frames: (0..count).map(create).collect(),
+            frame,
+        }
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Index of the copy for the frame being recorded
+    #[inline]
+    pub fn index(&self) -> usize {
+        (self.frame.load(Ordering::Relaxed) % self.frames.len() as u64) as usize
+    }
+
+    #[inline]
+    pub fn current(&self) -> &T {
+        &self.frames[self.index()]
+    }
+
+    #[inline]
+    pub fn current_mut(&mut self) -> &mut T {
+        let index = self.index();
+        &mut self.frames[index]
+    }
+
+    #[inline(always)]
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.frames.iter()
+    }
+
+    #[inline(always)]
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.frames.iter_mut()
+    }
+}
+
+impl<T: Bind> Bind for FramesInFlight<T> {
+    type Layout = T::Layout;
+
+    #[inline(always)]
+    fn bind_group(&self) -> &wgpu::BindGroup {
+        self.current().bind_group()
+    }
+
+    #[inline(always)]
+    fn layout(&self) -> &Self::Layout {
+        self.current().layout()
+    }
+}
+
+#[cfg(test)]
+mod frames_in_flight_tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use crate::prelude::*;
+
+    create_bind::bind!(FrameBind, FrameBindLayout {
+        UniformBuffers => {
+            index: u32 => 0 for VERTEX,
+        },
+    });
+
+    #[test]
+    fn test_rotation() {
+        let frame = Arc::new(AtomicU64::new(0));
+        let mut frames = FramesInFlight::from_counter(frame.clone(), 3, |index| index);
+
+        assert_eq!(frames.len(), 3);
+
+        for expected in [0, 1, 2, 0, 1] {
+            assert_eq!(frames.index(), expected);
+            assert_eq!(*frames.current(), expected);
+            frame.fetch_add(1, Ordering::Relaxed);
+        }
+
+        *frames.current_mut() += 10;
+        assert_eq!(frames.iter().copied().collect::<Vec<_>>(), [0, 1, 12]);
+    }
+
+    #[tokio::test]
+    async fn test_bind_current() {
+        let render_context = RenderContext::new(RenderContextConfig::default()).await;
+
+        let layout = FrameBindLayout::new(&render_context);
+
+        let frame = Arc::new(AtomicU64::new(0));
+        let frames = FramesInFlight::from_counter(frame.clone(), 2, |index| {
+            FrameBind::new(
+                &render_context,
+                layout.clone(),
+                UniformBuffer::new_init(&render_context, None, &(index as u32)),
+            )
+        });
+
+        for index in [0, 1, 0] {
+            let current = &frames.iter().as_slice()[index];
+
+            assert!(std::ptr::eq(frames.bind_group(), current.bind_group()));
+            assert!(std::ptr::eq(frames.layout(), current.layout()));
+
+            frame.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
@@ -1,4 +1,11 @@
-use std::fmt::Debug;
+use std::{
+    fmt::Debug,
+    mem,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
 
 use wgpu::SurfaceTexture;
 
@@ -13,6 +20,17 @@ pub struct WindowConfig {
     pub clear: Option<wgpu::Color>,
 }
 
+/// Something that happened to the surface while acquiring a frame, reported
+/// in order to `OnDrawCallback::surface_event` after the frame's draw callback
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SurfaceEvent {
+    /// The surface was lost or outdated and has been reconfigured,
+    /// the frame was still drawn
+    Reconfigured(wgpu::SurfaceError),
+    /// No surface texture could be acquired and the frame was skipped
+    Skipped(wgpu::SurfaceError),
+}
+
 #[derive(Debug)]
 pub struct Window {
     pub window: Arc<winit::window::Window>,
@@ -25,14 +43,26 @@ pub struct Window {
     pub depth_texture: Texture<Texture2D>,
 
     pub clear: Option<wgpu::Color>,
+
+    frame: Arc<AtomicU64>,
+    surface_events: Mutex<Vec<SurfaceEvent>>,
 }
 
 impl Window {
+    #[inline]
     pub fn new(
         window: Arc<winit::window::Window>,
         render_context: RenderContext,
         config: &WindowConfig,
     ) -> Self {
+        Self::try_new(window, render_context, config).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    pub fn try_new(
+        window: Arc<winit::window::Window>,
+        render_context: RenderContext,
+        config: &WindowConfig,
+    ) -> Result<Self, InitError> {
         let size = window.inner_size();
 
         let surface_config = wgpu::SurfaceConfiguration {
@@ -48,20 +78,22 @@ impl Window {
 
         let surface = unsafe { render_context.instance() }
             .create_surface(window.clone())
-            .unwrap();
+            .map_err(InitError::CreateSurface)?;
 
         surface.configure(unsafe { render_context.device() }, &surface_config);
 
         let depth_texture = Texture::create_depth_texture(&render_context, &surface_config);
 
-        Self {
+        Ok(Self {
             window,
             render_context,
             surface_config,
             surface,
             clear: config.clear,
             depth_texture,
-        }
+            frame: Arc::new(AtomicU64::new(0)),
+            surface_events: Mutex::new(Vec::new()),
+        })
     }
 
     #[inline]
@@ -87,13 +119,80 @@ impl Window {
         self.surface_config.format
     }
 
+    /// Number of frames presented so far
+    #[inline]
+    pub fn frame(&self) -> u64 {
+        self.frame.load(Ordering::Relaxed)
+    }
+
+    /// Number of copies needed for a resource written every frame, one for
+    /// each of the `desired_maximum_frame_latency` frames the GPU may still
+    /// be reading and one for the frame being recorded
+    #[inline]
+    pub fn frames_in_flight(&self) -> usize {
+        self.surface_config.desired_maximum_frame_latency as usize + 1
+    }
+
+    #[inline(always)]
+    pub(crate) fn frame_counter(&self) -> &Arc<AtomicU64> {
+        &self.frame
+    }
+
+    /// Reconfigures the surface with the stored `surface_config` and
+    /// recreates the depth texture to match
+    pub fn reconfigure(&mut self) {
+        let device = unsafe { self.render_context.device() };
+
+        if self.surface_config.width > 0 && self.surface_config.height > 0 {
+            self.surface.configure(device, &self.surface_config);
+        }
+
+        self.depth_texture =
+            Texture::create_depth_texture(&self.render_context, &self.surface_config);
+    }
+
+    /// The surface events since they were last taken, the handler takes
+    /// them after every draw to report them
+    #[inline]
+    pub fn take_surface_events(&mut self) -> Vec<SurfaceEvent> {
+        mem::take(self.surface_events.get_mut().unwrap())
+    }
+
+    /// Acquires the next surface texture, reconfiguring the surface and
+    /// retrying once if it was lost or has become outdated
+    ///
+    /// Any other error, or a failed retry, is returned so that the caller
+    /// can skip the frame, either way the events are reported to the
+    /// `OnDrawCallback`, and the handler recreates the depth texture through
+    /// `reconfigure` once the frame is done
+    pub fn try_output(&self) -> Result<wgpu::SurfaceTexture, wgpu::SurfaceError> {
+        let mut surface_events = self.surface_events.lock().unwrap();
+
+        acquire(
+            || self.surface.get_current_texture(),
+            || {
+                if self.surface_config.width > 0 && self.surface_config.height > 0 {
+                    self.surface.configure(
+                        unsafe { self.render_context.device() },
+                        &self.surface_config,
+                    );
+                }
+            },
+            |surface_event| surface_events.push(surface_event),
+        )
+    }
+
+    /// # Panics
+    /// Panics if no surface texture could be acquired,
+    /// see `try_output` to skip the frame instead
     #[inline]
     pub fn output(&self) -> wgpu::SurfaceTexture {
-        self.surface.get_current_texture().unwrap()
+        self.try_output()
+            .unwrap_or_else(|error| panic!("failed to acquire surface texture: {error}"))
     }
 
-    pub fn command_encoder(&self) -> WindowCommandEncoder<'_> {
-        let output = self.surface.get_current_texture().unwrap();
+    pub fn try_command_encoder(&self) -> Result<WindowCommandEncoder<'_>, wgpu::SurfaceError> {
+        let output = self.try_output()?;
 
         let view = unsafe {
             RawTextureView::new(
@@ -103,7 +202,7 @@ impl Window {
             )
         };
 
-        WindowCommandEncoder {
+        Ok(WindowCommandEncoder {
             command_encoder: self.render_context.command_encoder(),
             output,
             view,
@@ -114,7 +213,17 @@ impl Window {
                 store: wgpu::StoreOp::Store,
             }),
             stencil_ops: None,
-        }
+            frame: self.frame.clone(),
+        })
+    }
+
+    /// # Panics
+    /// Panics if no surface texture could be acquired,
+    /// see `try_command_encoder` to skip the frame instead
+    #[inline]
+    pub fn command_encoder(&self) -> WindowCommandEncoder<'_> {
+        self.try_command_encoder()
+            .unwrap_or_else(|error| panic!("failed to acquire surface texture: {error}"))
     }
 }
 
@@ -127,9 +236,27 @@ pub struct WindowCommandEncoder<'r> {
     depth_view: wgpu::TextureView,
     depth_ops: Option<wgpu::Operations<f32>>,
     stencil_ops: Option<wgpu::Operations<u32>>,
+    frame: Arc<AtomicU64>,
 }
 
 impl<'a> WindowCommandEncoder<'a> {
+    #[inline]
+    pub fn with_profiler<'p>(self, profiler: &'p mut Profiler) -> WindowCommandEncoder<'p>
+    where
+        'a: 'p,
+    {
+        WindowCommandEncoder {
+            command_encoder: self.command_encoder.with_profiler(profiler),
+            output: self.output,
+            view: self.view,
+            clear: self.clear,
+            depth_view: self.depth_view,
+            depth_ops: self.depth_ops,
+            stencil_ops: self.stencil_ops,
+            frame: self.frame,
+        }
+    }
+
     #[inline(always)]
     pub fn command_encoder(&self) -> &CommandEncoder<'_> {
         &self.command_encoder
@@ -206,6 +333,36 @@ impl<'a> WindowCommandEncoder<'a> {
         )
     }
 
+    /// Begins a render pass writing to the window's output as the first
+    /// color target and to each of `views` as the following ones, such as
+    /// a G-buffer or object-ID buffer drawn alongside color
+    pub fn render_pass_with_targets(
+        &'_ mut self,
+        views: &[&RawTextureView<Texture2D>],
+        load: Option<wgpu::LoadOp<wgpu::Color>>,
+        depth_stencil_attachment: bool,
+    ) -> RenderPass<'_> {
+        let targets: Vec<_> = std::iter::once(&self.view)
+            .chain(views.iter().copied())
+            .collect();
+
+        self.command_encoder.render_pass_targets(
+            &targets,
+            Some(load.unwrap_or(wgpu::LoadOp::Clear(
+                self.clear.unwrap_or(wgpu::Color::TRANSPARENT),
+            ))),
+            if depth_stencil_attachment {
+                Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &self.depth_view,
+                    depth_ops: self.depth_ops,
+                    stencil_ops: self.stencil_ops,
+                })
+            } else {
+                None
+            },
+        )
+    }
+
     pub fn render_pass_with(
         &'_ mut self,
         view: &RawTextureView<Texture2D>,
@@ -229,14 +386,95 @@ impl<'a> WindowCommandEncoder<'a> {
         )
     }
 
+    /// Submits the frame without presenting it, this still counts as
+    /// a presented frame for every `FramesInFlight` of the window
     pub fn submit(self) -> SurfaceTexture {
         self.command_encoder.submit();
+        self.frame.fetch_add(1, Ordering::Relaxed);
         self.output
     }
 
+    /// Submits the frame and presents it, moving every `FramesInFlight`
+    /// of the window on to its next copy
     #[inline]
     pub fn present(self) {
         self.command_encoder.submit();
         self.output.present();
+        self.frame.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Calls `get_texture`, calling `reconfigure` and retrying once if the
+/// surface was lost or outdated, and passes what happened to `report`
+fn acquire<T>(
+    mut get_texture: impl FnMut() -> Result<T, wgpu::SurfaceError>,
+    reconfigure: impl FnOnce(),
+    mut report: impl FnMut(SurfaceEvent),
+) -> Result<T, wgpu::SurfaceError> {
+    let result = match get_texture() {
+        Err(error @ (wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated)) => {
+            reconfigure();
+            report(SurfaceEvent::Reconfigured(error));
+            get_texture()
+        }
+        result => result,
+    };
+
+    if let Err(error) = &result {
+        report(SurfaceEvent::Skipped(error.clone()));
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod window_tests {
+    use super::acquire;
+    use crate::prelude::*;
+
+    fn events(
+        results: [Result<(), wgpu::SurfaceError>; 2],
+    ) -> (Result<(), wgpu::SurfaceError>, bool, Vec<SurfaceEvent>) {
+        let mut results = results.into_iter();
+        let mut reconfigured = false;
+        let mut surface_events = Vec::new();
+
+        let result = acquire(
+            || results.next().unwrap(),
+            || reconfigured = true,
+            |surface_event| surface_events.push(surface_event),
+        );
+
+        (result, reconfigured, surface_events)
+    }
+
+    #[test]
+    fn test_acquire() {
+        use wgpu::SurfaceError::{Lost, Outdated, Timeout};
+
+        assert_eq!(events([Ok(()), Err(Lost)]), (Ok(()), false, vec![]));
+
+        assert_eq!(
+            events([Err(Outdated), Ok(())]),
+            (Ok(()), true, vec![SurfaceEvent::Reconfigured(Outdated)])
+        );
+
+        // a failed retry keeps the reconfiguration along with the skip
+        assert_eq!(
+            events([Err(Lost), Err(Timeout)]),
+            (
+                Err(Timeout),
+                true,
+                vec![
+                    SurfaceEvent::Reconfigured(Lost),
+                    SurfaceEvent::Skipped(Timeout)
+                ]
+            )
+        );
+
+        assert_eq!(
+            events([Err(Timeout), Ok(())]),
+            (Err(Timeout), false, vec![SurfaceEvent::Skipped(Timeout)])
+        );
     }
 }
@@ -1,6 +1,9 @@
+pub mod composer;
 pub mod compute_layout;
 pub mod instanced_layout;
 pub mod layout;
+pub mod push_constants;
+pub mod reflection;
 
 pub mod instances;
 pub mod shaders;
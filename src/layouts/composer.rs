@@ -0,0 +1,486 @@
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
+
+use crate::prelude::*;
+
+/// A line of a shader before composition, by the name it was composed or
+/// included as and its line number starting from 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShaderLocation {
+    pub file: Arc<str>,
+    pub line: usize,
+}
+
+impl Display for ShaderLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)
+    }
+}
+
+/// Composes WGSL from named snippets before creating a shader module
+///
+/// Lines starting with `#` are directives
+///
+/// ```wgsl
+/// #include "r3/camera"
+/// #define SAMPLES 4
+/// #ifdef SHADOWS
+/// // only kept when `SHADOWS` is defined
+/// #else
+/// // otherwise
+/// #endif
+/// ```
+///
+/// Each snippet is included at most once, a defined name is replaced by its
+/// value wherever it appears as an identifier, and `#ifndef` and `#undef`
+/// work as in C
+#[derive(Debug, Clone)]
+pub struct ShaderComposer {
+    snippets: HashMap<String, Cow<'static, str>>,
+    defines: HashMap<String, String>,
+}
+
+impl Default for ShaderComposer {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShaderComposer {
+    /// `CameraUniform`, also declaring `camera` at binding 0 of
+    /// `CAMERA_GROUP` when it is defined
+    pub const CAMERA: &str = "r3/camera";
+    /// `TileVertex` and `TileInstance`, the inputs of `SimpleTileLayout`
+    pub const TILESET: &str = "r3/tileset";
+    /// `FullscreenVertex` and the `vs` entry point for post-processing
+    pub const FULLSCREEN: &str = "r3/fullscreen";
+
+    /// A composer with the crate snippets
+    pub fn new() -> Self {
+        let mut composer = Self::empty();
+
+        composer
+            .add_snippet(Self::CAMERA, include_str!("composer/camera.wgsl"))
+            .add_snippet(Self::TILESET, include_str!("composer/tileset.wgsl"))
+            .add_snippet(Self::FULLSCREEN, include_str!("composer/fullscreen.wgsl"));
+
+        composer
+    }
+
+    #[inline]
+    pub fn empty() -> Self {
+        Self {
+            snippets: HashMap::new(),
+            defines: HashMap::new(),
+        }
+    }
+
+    /// Makes `source` available to `#include "name"`, replacing any snippet
+    /// of the same name
+    #[inline]
+    pub fn add_snippet(
+        &mut self,
+        name: impl Into<String>,
+        source: impl Into<Cow<'static, str>>,
+    ) -> &mut Self {
+        self.snippets.insert(name.into(), source.into());
+        self
+    }
+
+    /// Defines `name` for every shader composed, as if each started with
+    /// `#define name value`
+    #[inline]
+    pub fn define(&mut self, name: impl Into<String>, value: impl Display) -> &mut Self {
+        self.defines.insert(name.into(), value.to_string());
+        self
+    }
+
+    #[inline]
+    pub fn undefine(&mut self, name: &str) -> &mut Self {
+        self.defines.remove(name);
+        self
+    }
+
+    /// Resolves the directives of `source`, named `file` in errors
+    pub fn compose(&self, file: &str, source: &str) -> Result<ComposedShader, ComposeError> {
+        let mut composition = Composition {
+            composer: self,
+            defines: self.defines.clone(),
+            included: HashSet::new(),
+            stack: Vec::new(),
+            source: String::with_capacity(source.len()),
+            lines: Vec::new(),
+        };
+
+        composition.process(file.into(), source)?;
+
+        Ok(ComposedShader {
+            source: composition.source,
+            lines: composition.lines,
+        })
+    }
+
+    /// Composes and checks the shader, reporting errors at their original
+    /// file and line, before creating the module
+    pub fn create_shader_module(
+        &self,
+        render_context: &RenderContext,
+        label: Option<&str>,
+        file: &str,
+        source: &str,
+    ) -> Result<wgpu::ShaderModule, ComposeError> {
+        let composed = self.compose(file, source)?;
+
+        composed.check()?;
+
+        Ok(render_context
+            .create_shader_module(label, wgpu::ShaderSource::Wgsl(composed.source.into())))
+    }
+}
+
+/// WGSL produced by a `ShaderComposer`, mapping each of its lines back to
+/// where it was written
+#[derive(Debug, Clone)]
+pub struct ComposedShader {
+    source: String,
+    lines: Vec<ShaderLocation>,
+}
+
+impl ComposedShader {
+    #[inline(always)]
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Where a line of the composed source, starting from 1, was written
+    #[inline]
+    pub fn location(&self, line: usize) -> Option<&ShaderLocation> {
+        self.lines.get(line.checked_sub(1)?)
+    }
+
+    /// Parses and validates the composed source with naga
+    pub fn check(&self) -> Result<(), ComposeError> {
+        let module =
+            naga::front::wgsl::parse_str(&self.source).map_err(|error| ComposeError::Shader {
+                message: error.message().to_owned(),
+                location: self.naga_location(error.location(&self.source)),
+            })?;
+
+        naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::all(),
+        )
+        .validate(&module)
+        .map_err(|error| ComposeError::Shader {
+            message: error.as_inner().to_string(),
+            location: self.naga_location(error.location(&self.source)),
+        })?;
+
+        Ok(())
+    }
+
+    fn naga_location(&self, location: Option<naga::SourceLocation>) -> Option<ShaderLocation> {
+        self.location(location?.line_number as usize).cloned()
+    }
+}
+
+impl From<ComposedShader> for String {
+    #[inline(always)]
+    fn from(composed: ComposedShader) -> Self {
+        composed.source
+    }
+}
+
+struct Composition<'c> {
+    composer: &'c ShaderComposer,
+    defines: HashMap<String, String>,
+    included: HashSet<String>,
+    stack: Vec<String>,
+    source: String,
+    lines: Vec<ShaderLocation>,
+}
+
+struct Conditional {
+    active: bool,
+    taken: bool,
+    has_else: bool,
+    location: ShaderLocation,
+}
+
+impl Composition<'_> {
+    fn process(&mut self, file: Arc<str>, source: &str) -> Result<(), ComposeError> {
+        let mut conditionals: Vec<Conditional> = Vec::new();
+
+        for (index, line) in source.lines().enumerate() {
+            let location = ShaderLocation {
+                file: file.clone(),
+                line: index + 1,
+            };
+
+            let active = conditionals
+                .last()
+                .is_none_or(|conditional| conditional.active);
+
+            let Some(directive) = line.trim_start().strip_prefix('#') else {
+                if active {
+                    self.push_line(line, location);
+                }
+                continue;
+            };
+
+            let (name, argument) = directive
+                .trim()
+                .split_once(char::is_whitespace)
+                .map_or((directive.trim(), ""), |(name, argument)| {
+                    (name, argument.trim())
+                });
+
+            match name {
+                "ifdef" | "ifndef" => {
+                    let defined = self.defines.contains_key(identifier(argument, &location)?);
+                    let taken = defined == (name == "ifdef");
+
+                    conditionals.push(Conditional {
+                        active: active && taken,
+                        taken,
+                        has_else: false,
+                        location,
+                    });
+                }
+                "else" => {
+                    let parent_active = conditionals
+                        .iter()
+                        .rev()
+                        .nth(1)
+                        .is_none_or(|conditional| conditional.active);
+
+                    let Some(conditional) = conditionals.last_mut() else {
+                        return Err(ComposeError::directive("#else without #ifdef", location));
+                    };
+                    if conditional.has_else {
+                        return Err(ComposeError::directive("#else after #else", location));
+                    }
+
+                    conditional.active = parent_active && !conditional.taken;
+                    conditional.has_else = true;
+                }
+                "endif" => {
+                    if conditionals.pop().is_none() {
+                        return Err(ComposeError::directive("#endif without #ifdef", location));
+                    }
+                }
+                _ if !active => {}
+                "define" => {
+                    let (define, value) = argument
+                        .split_once(char::is_whitespace)
+                        .map_or((argument, ""), |(define, value)| (define, value.trim()));
+
+                    let define = identifier(define, &location)?;
+                    self.defines.insert(define.to_owned(), value.to_owned());
+                }
+                "undef" => {
+                    let define = identifier(argument, &location)?;
+                    self.defines.remove(define);
+                }
+                "include" => self.include(argument, location)?,
+                _ => {
+                    return Err(ComposeError::directive(
+                        format!("unknown directive #{name}"),
+                        location,
+                    ))
+                }
+            }
+        }
+
+        match conditionals.pop() {
+            Some(conditional) => Err(ComposeError::directive(
+                "#ifdef without #endif",
+                conditional.location,
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn include(&mut self, argument: &str, location: ShaderLocation) -> Result<(), ComposeError> {
+        let name = argument
+            .strip_prefix('"')
+            .and_then(|name| name.strip_suffix('"'))
+            .ok_or_else(|| {
+                ComposeError::directive("expected #include \"name\"", location.clone())
+            })?;
+
+        if self.stack.iter().any(|included| included == name) {
+            return Err(ComposeError::RecursiveInclude {
+                name: name.to_owned(),
+                location,
+            });
+        }
+
+        if self.included.contains(name) {
+            return Ok(());
+        }
+
+        let composer = self.composer;
+
+        let Some(snippet) = composer.snippets.get(name) else {
+            return Err(ComposeError::MissingInclude {
+                name: name.to_owned(),
+                location,
+            });
+        };
+
+        self.stack.push(name.to_owned());
+        self.process(name.into(), snippet)?;
+        self.stack.pop();
+
+        self.included.insert(name.to_owned());
+
+        Ok(())
+    }
+
+    fn push_line(&mut self, line: &str, location: ShaderLocation) {
+        let mut rest = line;
+
+        while let Some(start) = rest.find(|c: char| c.is_alphabetic() || c == '_') {
+            // skip the tail of a number such as the `u` of `2u`
+            let number = rest[..start]
+                .chars()
+                .next_back()
+                .is_some_and(|c| c.is_alphanumeric());
+
+            let end = rest[start..]
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .map_or(rest.len(), |end| start + end);
+
+            let word = &rest[start..end];
+
+            self.source.push_str(&rest[..start]);
+
+            match self.defines.get(word) {
+                Some(value) if !number => self.source.push_str(value),
+                _ => self.source.push_str(word),
+            }
+
+            rest = &rest[end..];
+        }
+
+        self.source.push_str(rest);
+        self.source.push('\n');
+        self.lines.push(location);
+    }
+}
+
+fn identifier<'a>(argument: &'a str, location: &ShaderLocation) -> Result<&'a str, ComposeError> {
+    let valid = argument
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_')
+        && argument.chars().all(|c| c.is_alphanumeric() || c == '_');
+
+    if valid {
+        Ok(argument)
+    } else {
+        Err(ComposeError::directive(
+            format!("expected a name, found `{argument}`"),
+            location.clone(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod composer_tests {
+    use crate::prelude::{core::*, *};
+
+    #[test]
+    fn test_compose_camera() {
+        let mut composer = ShaderComposer::new();
+        composer.define("CAMERA_GROUP", 0);
+
+        let composed = composer
+            .compose(
+                "shader.wgsl",
+                "
+#include \"r3/camera\"
+#include \"r3/camera\"
+
+@vertex
+fn vs(@location(0) position: vec3<f32>) -> @builtin(position) vec4<f32> {
+    return camera.view_proj * vec4<f32>(position, 1.0);
+}
+",
+            )
+            .unwrap();
+
+        composed.check().unwrap();
+
+        assert_eq!(composed.source().matches("struct CameraUniform").count(), 1);
+
+        ShaderReflection::from_wgsl(composed.source())
+            .unwrap()
+            .validate(&[&CameraBindLayout::entries()])
+            .unwrap();
+    }
+
+    #[test]
+    fn test_compose_conditionals() {
+        let composer = ShaderComposer::empty();
+
+        let composed = composer
+            .compose(
+                "shader.wgsl",
+                "#define SAMPLES 4u
+#ifdef SAMPLES
+const samples: u32 = SAMPLES;
+#ifndef SAMPLES
+const nested: u32 = 0u;
+#endif
+#else
+const samples: u32 = 1u;
+#endif
+const count: u32 = 2u * SAMPLES;
+#define PRECISE
+PRECISE const scale: f32 = 1.0;",
+            )
+            .unwrap();
+
+        assert_eq!(
+            composed.source(),
+            "const samples: u32 = 4u;\nconst count: u32 = 2u * 4u;\n const scale: f32 = 1.0;\n"
+        );
+        assert_eq!(composed.location(2).unwrap().to_string(), "shader.wgsl:10");
+    }
+
+    #[test]
+    fn test_compose_errors() {
+        let mut composer = ShaderComposer::empty();
+        composer
+            .add_snippet("a", "#include \"b\"")
+            .add_snippet("b", "#include \"a\"")
+            .add_snippet("broken", "fn broken() -> u32 {\n    return 1.0 +;\n}");
+
+        let error = composer
+            .compose("shader.wgsl", "\n#include \"a\"")
+            .unwrap_err();
+        assert_eq!(error.location().unwrap().to_string(), "b:1");
+        assert!(matches!(error, ComposeError::RecursiveInclude { .. }));
+
+        let error = composer
+            .compose("shader.wgsl", "#include \"c\"")
+            .unwrap_err();
+        assert!(matches!(error, ComposeError::MissingInclude { .. }));
+
+        let error = composer.compose("shader.wgsl", "#ifdef A\n").unwrap_err();
+        assert_eq!(error.location().unwrap().to_string(), "shader.wgsl:1");
+
+        let error = composer
+            .compose("shader.wgsl", "// broken\n#include \"broken\"")
+            .unwrap()
+            .check()
+            .unwrap_err();
+        assert_eq!(error.location().unwrap().to_string(), "broken:2");
+    }
+}
@@ -0,0 +1,9 @@
+// Matches `CameraUniform`, bound by `CameraBind` at binding 0
+struct CameraUniform {
+    view_proj: mat4x4<f32>,
+};
+
+#ifdef CAMERA_GROUP
+@group(CAMERA_GROUP) @binding(0)
+var<uniform> camera: CameraUniform;
+#endif
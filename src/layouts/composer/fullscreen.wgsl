@@ -0,0 +1,21 @@
+// Fullscreen triangle for `RenderPass::draw_screen_quad`
+struct FullscreenVertex {
+    @location(0) uv: vec2<f32>,
+    @builtin(position) clip_position: vec4<f32>,
+};
+
+@vertex
+fn vs(
+    @builtin(vertex_index) vi: u32,
+) -> FullscreenVertex {
+    var out: FullscreenVertex;
+    // Triangle from (0,0) (2,0) (0,1)
+    out.uv = vec2<f32>(
+        f32((vi << 1u) & 2u),
+        f32(vi & 2u),
+    );
+    out.clip_position = vec4<f32>((out.uv * 2.0) - 1.0, 0.0, 1.0);
+    // Flip Y
+    out.uv.y = 1.0 - out.uv.y;
+    return out;
+}
@@ -0,0 +1,9 @@
+// Vertex and instance inputs of `SimpleTileLayout`
+struct TileVertex {
+    @location(0) position: vec2<f32>,
+};
+
+struct TileInstance {
+    @location(1) position: vec2<f32>,
+    @location(2) tile_id: u32,
+};
@@ -1,3 +1,5 @@
+use std::future::Future;
+
 use crate::prelude::*;
 
 pub type SharedComputeData<'a, L> = <L as ComputeLayout>::SharedData<'a>;
@@ -19,6 +21,13 @@ pub trait CreateComputePipeline: ComputeLayout {
         module: &wgpu::ShaderModule,
         compute_shader_config: ComputeShaderConfig,
     ) -> wgpu::ComputePipeline;
+
+    fn create_compute_pipeline_checked(
+        &self,
+        render_context: &RenderContext,
+        module: &wgpu::ShaderModule,
+        compute_shader_config: ComputeShaderConfig,
+    ) -> impl Future<Output = Result<wgpu::ComputePipeline, ValidationError>>;
 }
 impl<L: ComputeLayout> CreateComputePipeline for L {
     fn create_compute_pipeline(
@@ -30,6 +39,19 @@ impl<L: ComputeLayout> CreateComputePipeline for L {
         self.raw_layout()
             .create_compute_pipeline(render_context, module, compute_shader_config)
     }
+
+    fn create_compute_pipeline_checked(
+        &self,
+        render_context: &RenderContext,
+        module: &wgpu::ShaderModule,
+        compute_shader_config: ComputeShaderConfig,
+    ) -> impl Future<Output = Result<wgpu::ComputePipeline, ValidationError>> {
+        self.raw_layout().create_compute_pipeline_checked(
+            render_context,
+            module,
+            compute_shader_config,
+        )
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -39,7 +61,10 @@ pub struct RawComputeLayout {
 
 #[derive(Default, Debug, Clone)]
 pub struct ComputeLayoutConfig<'a> {
+    pub label: Option<&'a str>,
     pub bind_group_layouts: &'a [&'a wgpu::BindGroupLayout],
+    /// Push constant ranges, see `PushConstantLayout` for typed ranges
+    pub push_constant_ranges: &'a [wgpu::PushConstantRange],
 }
 
 impl RawComputeLayout {
@@ -54,9 +79,9 @@ impl RawComputeLayout {
     pub fn new(render_context: &RenderContext, config: ComputeLayoutConfig) -> Self {
         let pipeline_layout = unsafe { render_context.device() }.create_pipeline_layout(
             &wgpu::PipelineLayoutDescriptor {
-                label: None,
+                label: config.label,
                 bind_group_layouts: config.bind_group_layouts,
-                push_constant_ranges: &[],
+                push_constant_ranges: config.push_constant_ranges,
             },
         );
 
@@ -78,10 +103,25 @@ impl RawComputeLayout {
                 compilation_options: compute_shader_config
                     .compilation_options
                     .unwrap_or_default(),
-                cache: compute_shader_config.cache,
+                cache: compute_shader_config
+                    .cache
+                    .or(render_context.pipeline_cache()),
             },
         )
     }
+
+    pub async fn create_compute_pipeline_checked(
+        &self,
+        render_context: &RenderContext,
+        module: &wgpu::ShaderModule,
+        compute_shader_config: ComputeShaderConfig<'_>,
+    ) -> Result<wgpu::ComputePipeline, ValidationError> {
+        render_context
+            .capture_validation(|| {
+                self.create_compute_pipeline(render_context, module, compute_shader_config)
+            })
+            .await
+    }
 }
 
 #[derive(Default, Debug, Clone)]
@@ -51,14 +51,16 @@ macro_rules! simple_instances_impl {
                 }
             }
 
-            pub fn new(render_context: &RenderContext, $($buffer: &[$As]),*) -> Self {
+            pub fn new(render_context: &RenderContext, label: Option<&str>, $($buffer: &[$As]),*) -> Self {
                 let device = unsafe { render_context.device() };
 
                 let instance_count = get_len!($($buffer),*) as u32;
 
                 $(
                     let $buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                        label: None,
+                        label: label
+                            .map(|label| format!("{label} {}", stringify!($buffer)))
+                            .as_deref(),
                         contents: bytemuck::cast_slice($buffer),
                         usage: wgpu::BufferUsages::VERTEX,
                     });
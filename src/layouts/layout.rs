@@ -1,4 +1,4 @@
-use std::num::NonZeroU32;
+use std::{future::Future, num::NonZeroU32};
 
 use crate::prelude::*;
 
@@ -15,6 +15,70 @@ pub struct ShaderConfig<'a> {
     pub cache: Option<&'a wgpu::PipelineCache>,
     pub vertex_entry: Option<&'a str>,
     pub fragment_entry: Option<&'a str>,
+    /// Compilation options for both the vertex and fragment stage
+    pub compilation_options: Option<wgpu::PipelineCompilationOptions<'a>>,
+    /// Color targets to use instead of the layout's own
+    pub targets: Option<&'a [Option<wgpu::ColorTargetState>]>,
+    /// Blend state for every color target that can be blended, such as
+    /// float and normalized formats, other targets such as integer ids keep
+    /// their own. When transparent the default depth state still tests depth
+    /// but no longer writes it
+    pub blend: Option<BlendMode>,
+}
+
+/// Common blend states, applied to every blendable color target through
+/// `ShaderConfig`
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlendMode {
+    #[default]
+    Replace,
+    /// Mixes by the source alpha, for straight (non-premultiplied) colors
+    Alpha,
+    /// Mixes by the source alpha, for colors already multiplied by it
+    Premultiplied,
+    /// Adds the color scaled by its alpha, keeping the destination alpha
+    Additive,
+    /// Multiplies the destination color by the source color
+    Multiply,
+}
+
+impl BlendMode {
+    pub const fn state(self) -> wgpu::BlendState {
+        match self {
+            BlendMode::Replace => wgpu::BlendState::REPLACE,
+            BlendMode::Alpha => wgpu::BlendState::ALPHA_BLENDING,
+            BlendMode::Premultiplied => wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING,
+            BlendMode::Additive => wgpu::BlendState {
+                color: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::SrcAlpha,
+                    dst_factor: wgpu::BlendFactor::One,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha: Self::KEEP_ALPHA,
+            },
+            BlendMode::Multiply => wgpu::BlendState {
+                color: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::Dst,
+                    dst_factor: wgpu::BlendFactor::Zero,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha: Self::KEEP_ALPHA,
+            },
+        }
+    }
+
+    const KEEP_ALPHA: wgpu::BlendComponent = wgpu::BlendComponent {
+        src_factor: wgpu::BlendFactor::Zero,
+        dst_factor: wgpu::BlendFactor::One,
+        operation: wgpu::BlendOperation::Add,
+    };
+
+    /// Whether what is behind shows through, in which case the geometry
+    /// should be drawn back-to-front, such as with a `TransparentQueue`
+    #[inline(always)]
+    pub const fn is_transparent(self) -> bool {
+        !matches!(self, BlendMode::Replace)
+    }
 }
 
 pub trait Layout {
@@ -34,6 +98,13 @@ pub trait CreatePipeline {
         module: &wgpu::ShaderModule,
         config: ShaderConfig,
     ) -> wgpu::RenderPipeline;
+
+    fn create_pipeline_checked(
+        &self,
+        render_context: &RenderContext,
+        module: &wgpu::ShaderModule,
+        config: ShaderConfig,
+    ) -> impl Future<Output = Result<wgpu::RenderPipeline, ValidationError>>;
 }
 impl<L: Layout> CreatePipeline for L {
     fn create_pipeline(
@@ -45,26 +116,64 @@ impl<L: Layout> CreatePipeline for L {
         self.raw_layout()
             .create_pipeline(render_context, module, shader_config)
     }
+
+    fn create_pipeline_checked(
+        &self,
+        render_context: &RenderContext,
+        module: &wgpu::ShaderModule,
+        shader_config: ShaderConfig,
+    ) -> impl Future<Output = Result<wgpu::RenderPipeline, ValidationError>> {
+        self.raw_layout()
+            .create_pipeline_checked(render_context, module, shader_config)
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct RawLayout<V: VertexBufferLayout> {
     pipeline_layout: wgpu::PipelineLayout,
-    format: wgpu::TextureFormat,
+    targets: Box<[Option<wgpu::ColorTargetState>]>,
     __vertex: PhantomData<V>,
 }
 
 #[derive(Debug, Clone)]
 pub struct LayoutConfig<'a> {
+    pub label: Option<&'a str>,
     pub bind_group_layouts: &'a [&'a wgpu::BindGroupLayout],
+    /// Format of the single color target, used when `targets` is not set
     pub format: wgpu::TextureFormat,
+    /// Color targets written by the fragment shader, one for each
+    /// `@location`, each with its own format, blend state and write mask
+    pub targets: Option<&'a [Option<wgpu::ColorTargetState>]>,
+    /// Push constant ranges, see `PushConstantLayout` for typed ranges
+    pub push_constant_ranges: &'a [wgpu::PushConstantRange],
 }
 
 impl Default for LayoutConfig<'_> {
     fn default() -> Self {
         Self {
+            label: None,
             bind_group_layouts: &[],
             format: wgpu::TextureFormat::Bgra8UnormSrgb,
+            targets: None,
+            push_constant_ranges: &[],
+        }
+    }
+}
+
+#[inline]
+fn color_target(format: wgpu::TextureFormat) -> wgpu::ColorTargetState {
+    wgpu::ColorTargetState {
+        format,
+        blend: Some(wgpu::BlendState::REPLACE),
+        write_mask: wgpu::ColorWrites::ALL,
+    }
+}
+
+impl LayoutConfig<'_> {
+    fn color_targets(&self) -> Box<[Option<wgpu::ColorTargetState>]> {
+        match self.targets {
+            Some(targets) => targets.into(),
+            None => Box::new([Some(color_target(self.format))]),
         }
     }
 }
@@ -74,14 +183,35 @@ impl<V: VertexBufferLayout> RawLayout<V> {
         &self.pipeline_layout
     }
 
+    /// Format of the first color target
+    ///
+    /// # Panics
+    /// Panics if the layout has no color targets
     pub fn format(&self) -> wgpu::TextureFormat {
-        self.format
+        self.targets
+            .iter()
+            .flatten()
+            .next()
+            .expect("layout has no color targets")
+            .format
+    }
+
+    #[inline(always)]
+    pub fn targets(&self) -> &[Option<wgpu::ColorTargetState>] {
+        &self.targets
     }
 
     pub fn from_raw(pipeline_layout: wgpu::PipelineLayout, format: wgpu::TextureFormat) -> Self {
+        Self::from_raw_targets(pipeline_layout, &[Some(color_target(format))])
+    }
+
+    pub fn from_raw_targets(
+        pipeline_layout: wgpu::PipelineLayout,
+        targets: &[Option<wgpu::ColorTargetState>],
+    ) -> Self {
         Self {
             pipeline_layout,
-            format,
+            targets: targets.into(),
             __vertex: PhantomData,
         }
     }
@@ -89,15 +219,15 @@ impl<V: VertexBufferLayout> RawLayout<V> {
     pub fn new(render_context: &RenderContext, config: LayoutConfig) -> Self {
         let pipeline_layout = unsafe { render_context.device() }.create_pipeline_layout(
             &wgpu::PipelineLayoutDescriptor {
-                label: None,
+                label: config.label,
                 bind_group_layouts: config.bind_group_layouts,
-                push_constant_ranges: &[],
+                push_constant_ranges: config.push_constant_ranges,
             },
         );
 
         Self {
             pipeline_layout,
-            format: config.format,
+            targets: config.color_targets(),
             __vertex: PhantomData,
         }
     }
@@ -108,6 +238,31 @@ impl<V: VertexBufferLayout> RawLayout<V> {
         module: &wgpu::ShaderModule,
         shader_config: ShaderConfig,
     ) -> wgpu::RenderPipeline {
+        let features = unsafe { render_context.device() }.features();
+
+        let targets: Vec<_> = shader_config
+            .targets
+            .unwrap_or(&self.targets)
+            .iter()
+            .map(|target| {
+                let mut target = target.clone()?;
+
+                let blendable = target
+                    .format
+                    .guaranteed_format_features(features)
+                    .flags
+                    .contains(wgpu::TextureFormatFeatureFlags::BLENDABLE);
+
+                if let Some(blend) = shader_config.blend.filter(|_| blendable) {
+                    target.blend = Some(blend.state());
+                }
+
+                Some(target)
+            })
+            .collect();
+
+        let transparent = shader_config.blend.is_some_and(BlendMode::is_transparent);
+
         unsafe { render_context.device() }.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             label: shader_config.label,
             layout: Some(self.layout()),
@@ -115,17 +270,19 @@ impl<V: VertexBufferLayout> RawLayout<V> {
                 module,
                 entry_point: Some(shader_config.vertex_entry.unwrap_or("vs")),
                 buffers: V::DESC,
-                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                compilation_options: shader_config
+                    .compilation_options
+                    .clone()
+                    .unwrap_or_default(),
             },
             fragment: Some(wgpu::FragmentState {
                 module,
                 entry_point: Some(shader_config.fragment_entry.unwrap_or("fs")),
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: self.format(),
-                    blend: Some(wgpu::BlendState::REPLACE),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                targets: &targets,
+                compilation_options: shader_config
+                    .compilation_options
+                    .clone()
+                    .unwrap_or_default(),
             }),
             primitive: shader_config.primitive.unwrap_or(wgpu::PrimitiveState {
                 topology: wgpu::PrimitiveTopology::TriangleList,
@@ -140,7 +297,7 @@ impl<V: VertexBufferLayout> RawLayout<V> {
                 .depth_stencil
                 .unwrap_or(Some(wgpu::DepthStencilState {
                     format: wgpu::TextureFormat::Depth32Float,
-                    depth_write_enabled: true,
+                    depth_write_enabled: !transparent,
                     depth_compare: wgpu::CompareFunction::Less,
                     stencil: wgpu::StencilState::default(),
                     bias: wgpu::DepthBiasState::default(),
@@ -151,7 +308,137 @@ impl<V: VertexBufferLayout> RawLayout<V> {
                 alpha_to_coverage_enabled: false,
             }),
             multiview: shader_config.multiview,
-            cache: shader_config.cache,
+            cache: shader_config.cache.or(render_context.pipeline_cache()),
         })
     }
+
+    pub async fn create_pipeline_checked(
+        &self,
+        render_context: &RenderContext,
+        module: &wgpu::ShaderModule,
+        shader_config: ShaderConfig<'_>,
+    ) -> Result<wgpu::RenderPipeline, ValidationError> {
+        render_context
+            .capture_validation(|| self.create_pipeline(render_context, module, shader_config))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod layout_tests {
+    use crate::prelude::*;
+
+    const TINT_SHADER: &str = r#"
+        @group(0) @binding(0)
+        var<uniform> tint: vec4<f32>;
+
+        @vertex
+        fn vs(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+            return vec4<f32>(f32(index), 0.0, 0.0, 1.0);
+        }
+
+        @fragment
+        fn fs() -> @location(0) vec4<f32> {
+            return tint;
+        }
+    "#;
+
+    #[tokio::test]
+    async fn test_create_pipeline_checked_mismatch() {
+        let render_context = RenderContext::new(RenderContextConfig::default()).await;
+
+        let module = render_context
+            .create_shader_module_checked(None, wgpu::ShaderSource::Wgsl(TINT_SHADER.into()))
+            .await
+            .unwrap();
+
+        let bind_group_layout = unsafe { render_context.device() }.create_bind_group_layout(
+            &wgpu::BindGroupLayoutDescriptor {
+                label: None,
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+            },
+        );
+
+        let layout = RawLayout::<Void>::new(
+            &render_context,
+            LayoutConfig {
+                bind_group_layouts: &[&bind_group_layout],
+                ..default()
+            },
+        );
+        let pipeline = layout
+            .create_pipeline_checked(&render_context, &module, default())
+            .await;
+        assert!(pipeline.is_ok());
+
+        // the shader reads group 0, which this layout does not provide
+        let empty_layout = RawLayout::<Void>::new(&render_context, default());
+        let pipeline = empty_layout
+            .create_pipeline_checked(&render_context, &module, default())
+            .await;
+        assert!(matches!(pipeline, Err(ValidationError { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_blend_skips_integer_targets() {
+        let render_context = RenderContext::new(RenderContextConfig::default()).await;
+
+        let module = render_context.create_shader_module(
+            None,
+            wgpu::ShaderSource::Wgsl(
+                "
+                struct Targets {
+                    @location(0) color: vec4<f32>,
+                    @location(1) id: u32,
+                };
+
+                @vertex
+                fn vs() -> @builtin(position) vec4<f32> {
+                    return vec4<f32>(0.0, 0.0, 0.0, 1.0);
+                }
+
+                @fragment
+                fn fs() -> Targets {
+                    return Targets(vec4<f32>(1.0, 1.0, 1.0, 0.5), 7u);
+                }
+                "
+                .into(),
+            ),
+        );
+
+        let layout = RawLayout::<Void>::new(
+            &render_context,
+            LayoutConfig {
+                targets: Some(&[
+                    Some(wgpu::TextureFormat::Rgba8Unorm.into()),
+                    Some(wgpu::TextureFormat::R32Uint.into()),
+                ]),
+                ..default()
+            },
+        );
+
+        // blending an integer target is a validation error, so the preset
+        // must only reach the color target
+        let pipeline = layout
+            .create_pipeline_checked(
+                &render_context,
+                &module,
+                ShaderConfig {
+                    depth_stencil: Some(None),
+                    blend: Some(BlendMode::Alpha),
+                    ..default()
+                },
+            )
+            .await;
+        assert!(pipeline.is_ok());
+    }
 }
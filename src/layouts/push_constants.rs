@@ -0,0 +1,439 @@
+use std::slice;
+
+use crate::prelude::*;
+
+/// Layout of `PushConstants<T>`, a push constant range holding `T` when the
+/// device has `Features::PUSH_CONSTANTS`, otherwise a bind group with a
+/// uniform buffer of `T` at binding 0
+///
+/// The shader declares whichever of the two `is_push_constant` reports
+///
+/// ```wgsl
+/// var<push_constant> settings: Settings;
+/// // or
+/// @group(2) @binding(0)
+/// var<uniform> settings: Settings;
+/// ```
+#[derive(Debug, Clone)]
+pub struct PushConstantLayout<T> {
+    range: wgpu::PushConstantRange,
+    fallback: Option<wgpu::BindGroupLayout>,
+    __data: PhantomData<T>,
+}
+
+impl<T: UniformData> PushConstantLayout<T> {
+    pub const LABEL: &str = "PushConstantLayout";
+
+    /// Features the device must have been requested with to use push
+    /// constants instead of the uniform fallback
+    pub const FEATURES: wgpu::Features = wgpu::Features::PUSH_CONSTANTS;
+
+    pub const SIZE: u32 = {
+        let size = size_of::<T>() as u32;
+        assert!(
+            size.is_multiple_of(4),
+            "push constants must be a multiple of 4 bytes"
+        );
+        size
+    };
+
+    /// Raises `limits` so that `T` fits in the push constant range
+    #[inline]
+    pub fn limits(limits: wgpu::Limits) -> wgpu::Limits {
+        wgpu::Limits {
+            max_push_constant_size: limits.max_push_constant_size.max(Self::SIZE),
+            ..limits
+        }
+    }
+
+    /// Range covering `T` from offset 0 for the given stages
+    #[inline]
+    pub const fn range(stages: wgpu::ShaderStages) -> wgpu::PushConstantRange {
+        wgpu::PushConstantRange {
+            stages,
+            range: 0..Self::SIZE,
+        }
+    }
+
+    #[inline]
+    pub fn is_supported(render_context: &RenderContext) -> bool {
+        let device = unsafe { render_context.device() };
+
+        device.features().contains(Self::FEATURES)
+            && device.limits().max_push_constant_size >= Self::SIZE
+    }
+
+    /// Uses push constants when the device supports them and falls back to
+    /// a uniform buffer otherwise
+    pub fn new(render_context: &RenderContext, stages: wgpu::ShaderStages) -> Self {
+        if Self::is_supported(render_context) {
+            Self {
+                range: Self::range(stages),
+                fallback: None,
+                __data: PhantomData,
+            }
+        } else {
+            Self::new_fallback(render_context, stages)
+        }
+    }
+
+    /// Always uses the uniform buffer, even when push constants are supported
+    pub fn new_fallback(render_context: &RenderContext, stages: wgpu::ShaderStages) -> Self {
+        let device = unsafe { render_context.device() };
+
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some(Self::LABEL),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: stages,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+
+        Self {
+            range: Self::range(stages),
+            fallback: Some(layout),
+            __data: PhantomData,
+        }
+    }
+}
+
+impl<T> PushConstantLayout<T> {
+    #[inline(always)]
+    pub fn is_push_constant(&self) -> bool {
+        self.fallback.is_none()
+    }
+
+    #[inline(always)]
+    pub fn stages(&self) -> wgpu::ShaderStages {
+        self.range.stages
+    }
+
+    /// Push constant ranges for `LayoutConfig` or `ComputeLayoutConfig`,
+    /// empty when falling back to a uniform buffer
+    #[inline]
+    pub fn push_constant_ranges(&self) -> &[wgpu::PushConstantRange] {
+        match self.fallback {
+            Some(_) => &[],
+            None => slice::from_ref(&self.range),
+        }
+    }
+
+    /// Bind group layout of the uniform fallback, to be placed in the
+    /// pipeline layout at the group the `PushConstants` are created with
+    #[inline(always)]
+    pub fn bind_group_layout(&self) -> Option<&wgpu::BindGroupLayout> {
+        self.fallback.as_ref()
+    }
+}
+
+/// `Shader::Settings` or `ComputeShader::Settings` holding a `T` that is
+/// written as push constants, or bound as a uniform buffer at `group` when
+/// its layout falls back to one
+///
+/// With the fallback every draw in a submission sees the last value `set`
+/// before it, whereas push constants can change between draws
+#[derive(Debug, Clone)]
+pub struct PushConstants<T: 'static> {
+    value: T,
+    stages: wgpu::ShaderStages,
+    fallback: Option<PushConstantFallback<T>>,
+}
+
+#[derive(Debug, Clone)]
+struct PushConstantFallback<T: 'static> {
+    group: u32,
+    buffer: UniformBuffer<T>,
+    bind_group: wgpu::BindGroup,
+}
+
+impl<T: UniformData> PushConstants<T> {
+    pub fn new(
+        render_context: &RenderContext,
+        layout: &PushConstantLayout<T>,
+        group: u32,
+        value: T,
+    ) -> Self {
+        let fallback = layout.bind_group_layout().map(|bind_group_layout| {
+            let buffer = UniformBuffer::new_init(
+                render_context,
+                Some(PushConstantLayout::<T>::LABEL),
+                &value,
+            );
+
+            let bind_group =
+                unsafe { render_context.device() }.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some(PushConstantLayout::<T>::LABEL),
+                    layout: bind_group_layout,
+                    entries: &[wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: unsafe { buffer.wgpu_buffer() }.as_entire_binding(),
+                    }],
+                });
+
+            PushConstantFallback {
+                group,
+                buffer,
+                bind_group,
+            }
+        });
+
+        Self {
+            value,
+            stages: layout.stages(),
+            fallback,
+        }
+    }
+
+    #[inline(always)]
+    pub fn get(&self) -> &T {
+        &self.value
+    }
+
+    /// Replaces the value, writing it to the uniform buffer when falling back
+    pub fn set(&mut self, render_context: &RenderContext, value: T) {
+        if let Some(fallback) = &self.fallback {
+            fallback.buffer.write(render_context, &value);
+        }
+
+        self.value = value;
+    }
+
+    /// Writes the push constants, or binds the uniform buffer, for the
+    /// following draws, to be called from `Shader::apply_settings`
+    pub fn apply(&self, render_pass: &mut wgpu::RenderPass) {
+        match &self.fallback {
+            Some(fallback) => render_pass.set_bind_group(fallback.group, &fallback.bind_group, &[]),
+            None => render_pass.set_push_constants(self.stages, 0, bytemuck::bytes_of(&self.value)),
+        }
+    }
+
+    /// Writes the push constants, or binds the uniform buffer, for the
+    /// following dispatches, to be called from `ComputeShader::apply_settings`
+    pub fn apply_compute(&self, compute_pass: &mut wgpu::ComputePass) {
+        match &self.fallback {
+            Some(fallback) => {
+                compute_pass.set_bind_group(fallback.group, &fallback.bind_group, &[])
+            }
+            None => compute_pass.set_push_constants(0, bytemuck::bytes_of(&self.value)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod push_constants_tests {
+    use crate::prelude::*;
+
+    #[repr(C)]
+    #[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+    struct Tint {
+        color: [f32; 4],
+    }
+
+    create_wgsl_layout::wgsl_layout!(Tint { color: [f32; 4] });
+
+    /// Draws a full screen triangle with the color of `tint` into a single
+    /// texel, through push constants or the uniform fallback, and reads it back
+    fn draw_tint(
+        render_context: &RenderContext,
+        layout: &PushConstantLayout<Tint>,
+        tint: &PushConstants<Tint>,
+    ) -> Vec<u8> {
+        let declaration = if layout.is_push_constant() {
+            "var<push_constant> tint: Tint;"
+        } else {
+            "@group(0) @binding(0) var<uniform> tint: Tint;"
+        };
+
+        let source = format!(
+            "
+            struct Tint {{
+                color: vec4<f32>,
+            }};
+
+            {declaration}
+
+            @vertex
+            fn vs(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {{
+                let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+                return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+            }}
+
+            @fragment
+            fn fs() -> @location(0) vec4<f32> {{
+                return tint.color;
+            }}
+            "
+        );
+
+        let raw_layout = RawLayout::<Void>::new(
+            render_context,
+            LayoutConfig {
+                bind_group_layouts: &layout.bind_group_layout().into_iter().collect::<Vec<_>>(),
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                push_constant_ranges: layout.push_constant_ranges(),
+                ..default()
+            },
+        );
+
+        let module =
+            render_context.create_shader_module(None, wgpu::ShaderSource::Wgsl(source.into()));
+
+        let pipeline = raw_layout.create_pipeline(
+            render_context,
+            &module,
+            ShaderConfig {
+                depth_stencil: Some(None),
+                ..default()
+            },
+        );
+
+        let target = RenderTarget::new(
+            render_context,
+            1,
+            1,
+            &RenderTargetConfig {
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                ..default()
+            },
+        );
+
+        let mut encoder = render_context.command_encoder();
+        let mut render_pass = encoder.render_target_pass(&target, None, false);
+        let inner = unsafe { render_pass.inner() };
+        inner.set_pipeline(&pipeline);
+        tint.apply(inner);
+        inner.draw(0..3, 0..1);
+        drop(render_pass);
+        encoder.submit();
+
+        target.read(render_context)
+    }
+
+    #[test]
+    fn test_push_constant_range() {
+        let range = PushConstantLayout::<Tint>::range(wgpu::ShaderStages::FRAGMENT);
+
+        assert_eq!(range.range, 0..16);
+        assert_eq!(
+            PushConstantLayout::<Tint>::limits(wgpu::Limits::default()).max_push_constant_size,
+            16
+        );
+    }
+
+    #[tokio::test]
+    async fn test_push_constant_fallback() {
+        let render_context = RenderContext::new(RenderContextConfig::default()).await;
+
+        let layout =
+            PushConstantLayout::<Tint>::new_fallback(&render_context, wgpu::ShaderStages::FRAGMENT);
+
+        assert!(!layout.is_push_constant());
+        assert!(layout.push_constant_ranges().is_empty());
+
+        let bind_group_layout = layout.bind_group_layout().unwrap();
+
+        let raw_layout = render_context
+            .capture_validation(|| {
+                RawLayout::<Void>::new(
+                    &render_context,
+                    LayoutConfig {
+                        bind_group_layouts: &[bind_group_layout],
+                        push_constant_ranges: layout.push_constant_ranges(),
+                        ..default()
+                    },
+                )
+            })
+            .await;
+        assert!(raw_layout.is_ok());
+
+        let mut push_constants = PushConstants::new(
+            &render_context,
+            &layout,
+            0,
+            Tint {
+                color: [1.0, 0.0, 0.0, 1.0],
+            },
+        );
+
+        push_constants.set(
+            &render_context,
+            Tint {
+                color: [0.0, 1.0, 0.0, 1.0],
+            },
+        );
+        assert_eq!(push_constants.get().color, [0.0, 1.0, 0.0, 1.0]);
+
+        assert_eq!(
+            draw_tint(&render_context, &layout, &push_constants),
+            [0, 255, 0, 255]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_push_constant_supported() {
+        let render_context = match RenderContext::try_new(RenderContextConfig {
+            features: Some(PushConstantLayout::<Tint>::FEATURES),
+            limits: Some(PushConstantLayout::<Tint>::limits(wgpu::Limits::default())),
+            ..default()
+        })
+        .await
+        {
+            Ok(render_context) => render_context,
+            // the adapter has no push constants, which the fallback covers
+            Err(InitError::RequestDevice { .. }) => return,
+            Err(error) => panic!("{error}"),
+        };
+
+        let layout = PushConstantLayout::<Tint>::new(&render_context, wgpu::ShaderStages::FRAGMENT);
+
+        assert!(layout.is_push_constant());
+        assert!(layout.bind_group_layout().is_none());
+        assert_eq!(
+            layout.push_constant_ranges(),
+            [PushConstantLayout::<Tint>::range(
+                wgpu::ShaderStages::FRAGMENT
+            )]
+        );
+
+        let raw_layout = render_context
+            .capture_validation(|| {
+                RawLayout::<Void>::new(
+                    &render_context,
+                    LayoutConfig {
+                        push_constant_ranges: layout.push_constant_ranges(),
+                        ..default()
+                    },
+                )
+            })
+            .await;
+        assert!(raw_layout.is_ok());
+
+        let mut push_constants = PushConstants::new(
+            &render_context,
+            &layout,
+            0,
+            Tint {
+                color: [1.0, 0.0, 0.0, 1.0],
+            },
+        );
+
+        push_constants.set(
+            &render_context,
+            Tint {
+                color: [0.0, 0.0, 1.0, 1.0],
+            },
+        );
+        assert_eq!(push_constants.get().color, [0.0, 0.0, 1.0, 1.0]);
+
+        assert_eq!(
+            draw_tint(&render_context, &layout, &push_constants),
+            [0, 0, 255, 255]
+        );
+    }
+}
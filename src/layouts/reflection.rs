@@ -0,0 +1,608 @@
+use std::num::{NonZeroU32, NonZeroU64};
+
+use crate::prelude::*;
+
+/// A resource declared with `@group` and `@binding` in a shader
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReflectedBinding {
+    pub name: Option<String>,
+    pub group: u32,
+    pub binding: u32,
+    /// Stages of the entry points that use the resource,
+    /// empty when none of them do
+    pub visibility: wgpu::ShaderStages,
+    pub ty: wgpu::BindingType,
+    pub count: Option<NonZeroU32>,
+    /// Whether the resource is a `binding_array` declared without a size,
+    /// which any count in the layout satisfies
+    pub unbounded: bool,
+}
+
+impl ReflectedBinding {
+    #[inline]
+    pub fn layout_entry(&self) -> wgpu::BindGroupLayoutEntry {
+        wgpu::BindGroupLayoutEntry {
+            binding: self.binding,
+            visibility: self.visibility,
+            ty: self.ty,
+            count: self.count,
+        }
+    }
+}
+
+/// The resource bindings of a WGSL module, parsed and validated with naga
+///
+/// It can generate bind group layouts that match the shader, or check
+/// layouts declared with `bind!` or `#[derive(Bind)]` against it before
+/// any pipeline is created
+#[derive(Debug, Clone)]
+pub struct ShaderReflection {
+    bindings: Vec<ReflectedBinding>,
+}
+
+impl ShaderReflection {
+    pub fn from_wgsl(source: &str) -> Result<Self, ReflectionError> {
+        let module = naga::front::wgsl::parse_str(source)
+            .map_err(|error| ReflectionError::Parse(error.emit_to_string(source)))?;
+
+        let info = naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::all(),
+        )
+        .validate(&module)
+        .map_err(|error| ReflectionError::Validation(error.emit_to_string(source)))?;
+
+        let mut bindings = Vec::new();
+
+        for (handle, global) in module.global_variables.iter() {
+            let Some(resource) = &global.binding else {
+                continue;
+            };
+
+            let mut visibility = wgpu::ShaderStages::NONE;
+
+            for (index, entry_point) in module.entry_points.iter().enumerate() {
+                if !info.get_entry_point(index)[handle].is_empty() {
+                    visibility |= stage(entry_point.stage);
+                }
+            }
+
+            let (ty, count, unbounded) = match module.types[global.ty].inner {
+                naga::TypeInner::BindingArray { base, size } => match size {
+                    naga::ArraySize::Constant(size) => (base, Some(size), false),
+                    _ => (base, None, true),
+                },
+                _ => (global.ty, None, false),
+            };
+
+            let Some(ty) = binding_type(&module, global.space, ty) else {
+                continue;
+            };
+
+            bindings.push(ReflectedBinding {
+                name: global.name.clone(),
+                group: resource.group,
+                binding: resource.binding,
+                visibility,
+                ty,
+                count,
+                unbounded,
+            });
+        }
+
+        bindings.sort_by_key(|binding| (binding.group, binding.binding));
+
+        Ok(Self { bindings })
+    }
+
+    #[inline(always)]
+    pub fn bindings(&self) -> &[ReflectedBinding] {
+        &self.bindings
+    }
+
+    /// Number of bind groups a pipeline layout for the shader needs,
+    /// one past the highest group it declares
+    #[inline]
+    pub fn group_count(&self) -> u32 {
+        self.bindings.last().map_or(0, |binding| binding.group + 1)
+    }
+
+    #[inline]
+    pub fn group(&self, group: u32) -> impl Iterator<Item = &ReflectedBinding> {
+        self.bindings
+            .iter()
+            .filter(move |binding| binding.group == group)
+    }
+
+    /// Layout entries matching the bindings of `group`, buffers are not
+    /// given dynamic offsets and samplers are filtering unless they are
+    /// comparison samplers
+    ///
+    /// Unbounded binding arrays have no count in the shader, so theirs
+    /// must be set before the entries are used
+    #[inline]
+    pub fn layout_entries(&self, group: u32) -> Vec<wgpu::BindGroupLayoutEntry> {
+        self.group(group)
+            .map(ReflectedBinding::layout_entry)
+            .collect()
+    }
+
+    #[inline]
+    pub fn create_bind_group_layout(
+        &self,
+        render_context: &RenderContext,
+        label: Option<&str>,
+        group: u32,
+    ) -> wgpu::BindGroupLayout {
+        unsafe { render_context.device() }.create_bind_group_layout(
+            &wgpu::BindGroupLayoutDescriptor {
+                label,
+                entries: &self.layout_entries(group),
+            },
+        )
+    }
+
+    /// Checks that `entries` provide every binding of `group` that the
+    /// shader uses, with a compatible type and enough visibility
+    pub fn validate_group(
+        &self,
+        group: u32,
+        entries: &[wgpu::BindGroupLayoutEntry],
+    ) -> Result<(), ReflectionError> {
+        let mismatches = self.group_mismatches(group, Some(entries));
+
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(ReflectionError::Mismatch(mismatches))
+        }
+    }
+
+    #[inline]
+    pub fn validate_bind_layout<L: BindLayoutEntries>(
+        &self,
+        group: u32,
+    ) -> Result<(), ReflectionError> {
+        self.validate_group(group, &L::entries())
+    }
+
+    /// Checks a whole pipeline layout, given the entries of each of its bind
+    /// group layouts in order, as they are passed to `LayoutConfig`
+    pub fn validate(
+        &self,
+        groups: &[&[wgpu::BindGroupLayoutEntry]],
+    ) -> Result<(), ReflectionError> {
+        let mismatches: Vec<_> = (0..self.group_count())
+            .flat_map(|group| self.group_mismatches(group, groups.get(group as usize).copied()))
+            .collect();
+
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(ReflectionError::Mismatch(mismatches))
+        }
+    }
+
+    fn group_mismatches(
+        &self,
+        group: u32,
+        entries: Option<&[wgpu::BindGroupLayoutEntry]>,
+    ) -> Vec<BindingMismatch> {
+        let mut mismatches = Vec::new();
+
+        for binding in self.group(group) {
+            if binding.visibility.is_empty() {
+                continue;
+            }
+
+            let mut mismatch = |kind| {
+                mismatches.push(BindingMismatch {
+                    name: binding.name.clone(),
+                    group,
+                    binding: binding.binding,
+                    kind,
+                })
+            };
+
+            let Some(entry) = entries
+                .unwrap_or_default()
+                .iter()
+                .find(|entry| entry.binding == binding.binding)
+            else {
+                mismatch(MismatchKind::Missing);
+                continue;
+            };
+
+            if !compatible(&binding.ty, &entry.ty) {
+                mismatch(MismatchKind::Type {
+                    shader: binding.ty,
+                    layout: entry.ty,
+                });
+            }
+
+            if !entry.visibility.contains(binding.visibility) {
+                mismatch(MismatchKind::Visibility {
+                    shader: binding.visibility,
+                    layout: entry.visibility,
+                });
+            }
+
+            let count_matches = match binding.unbounded {
+                true => entry.count.is_some(),
+                false => entry.count == binding.count,
+            };
+
+            if !count_matches {
+                mismatch(MismatchKind::Count {
+                    shader: binding.count,
+                    layout: entry.count,
+                });
+            }
+        }
+
+        mismatches
+    }
+}
+
+/// A binding of a shader that a layout does not provide as declared
+#[derive(Debug, Clone, PartialEq)]
+pub struct BindingMismatch {
+    pub name: Option<String>,
+    pub group: u32,
+    pub binding: u32,
+    pub kind: MismatchKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MismatchKind {
+    Missing,
+    Type {
+        shader: wgpu::BindingType,
+        layout: wgpu::BindingType,
+    },
+    Visibility {
+        shader: wgpu::ShaderStages,
+        layout: wgpu::ShaderStages,
+    },
+    Count {
+        shader: Option<NonZeroU32>,
+        layout: Option<NonZeroU32>,
+    },
+}
+
+impl std::fmt::Display for BindingMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "@group({}) @binding({})", self.group, self.binding)?;
+
+        if let Some(name) = &self.name {
+            write!(f, " `{name}`")?;
+        }
+
+        match &self.kind {
+            MismatchKind::Missing => {
+                write!(f, " is used by the shader but missing from the layout")
+            }
+            MismatchKind::Type { shader, layout } => {
+                write!(
+                    f,
+                    " is {shader:?} in the shader but {layout:?} in the layout"
+                )
+            }
+            MismatchKind::Visibility { shader, layout } => write!(
+                f,
+                " is used in {shader:?} but only visible to {layout:?} in the layout"
+            ),
+            MismatchKind::Count { shader, layout } => write!(
+                f,
+                " has a count of {shader:?} in the shader but {layout:?} in the layout"
+            ),
+        }
+    }
+}
+
+fn stage(stage: naga::ShaderStage) -> wgpu::ShaderStages {
+    match stage {
+        naga::ShaderStage::Vertex => wgpu::ShaderStages::VERTEX,
+        naga::ShaderStage::Fragment => wgpu::ShaderStages::FRAGMENT,
+        naga::ShaderStage::Compute => wgpu::ShaderStages::COMPUTE,
+        naga::ShaderStage::Task => wgpu::ShaderStages::TASK,
+        naga::ShaderStage::Mesh => wgpu::ShaderStages::MESH,
+    }
+}
+
+fn binding_type(
+    module: &naga::Module,
+    space: naga::AddressSpace,
+    ty: naga::Handle<naga::Type>,
+) -> Option<wgpu::BindingType> {
+    let inner = &module.types[ty].inner;
+
+    let binding_type = match space {
+        naga::AddressSpace::Uniform => wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: NonZeroU64::new(inner.size(module.to_ctx()) as u64),
+        },
+        naga::AddressSpace::Storage { access } => wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Storage {
+                read_only: !access.contains(naga::StorageAccess::STORE),
+            },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        naga::AddressSpace::Handle => match *inner {
+            naga::TypeInner::Sampler { comparison } => wgpu::BindingType::Sampler(if comparison {
+                wgpu::SamplerBindingType::Comparison
+            } else {
+                wgpu::SamplerBindingType::Filtering
+            }),
+            naga::TypeInner::Image {
+                dim,
+                arrayed,
+                class,
+            } => {
+                let view_dimension = match (dim, arrayed) {
+                    (naga::ImageDimension::D1, _) => wgpu::TextureViewDimension::D1,
+                    (naga::ImageDimension::D2, false) => wgpu::TextureViewDimension::D2,
+                    (naga::ImageDimension::D2, true) => wgpu::TextureViewDimension::D2Array,
+                    (naga::ImageDimension::D3, _) => wgpu::TextureViewDimension::D3,
+                    (naga::ImageDimension::Cube, false) => wgpu::TextureViewDimension::Cube,
+                    (naga::ImageDimension::Cube, true) => wgpu::TextureViewDimension::CubeArray,
+                };
+
+                match class {
+                    naga::ImageClass::Sampled { kind, multi } => wgpu::BindingType::Texture {
+                        sample_type: match kind {
+                            naga::ScalarKind::Sint => wgpu::TextureSampleType::Sint,
+                            naga::ScalarKind::Uint => wgpu::TextureSampleType::Uint,
+                            _ => wgpu::TextureSampleType::Float { filterable: true },
+                        },
+                        view_dimension,
+                        multisampled: multi,
+                    },
+                    naga::ImageClass::Depth { multi } => wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Depth,
+                        view_dimension,
+                        multisampled: multi,
+                    },
+                    naga::ImageClass::Storage { format, access } => {
+                        wgpu::BindingType::StorageTexture {
+                            access: if access.contains(naga::StorageAccess::ATOMIC) {
+                                wgpu::StorageTextureAccess::Atomic
+                            } else if access.contains(naga::StorageAccess::LOAD) {
+                                if access.contains(naga::StorageAccess::STORE) {
+                                    wgpu::StorageTextureAccess::ReadWrite
+                                } else {
+                                    wgpu::StorageTextureAccess::ReadOnly
+                                }
+                            } else {
+                                wgpu::StorageTextureAccess::WriteOnly
+                            },
+                            format: storage_format(format),
+                            view_dimension,
+                        }
+                    }
+                    naga::ImageClass::External => wgpu::BindingType::ExternalTexture,
+                }
+            }
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    Some(binding_type)
+}
+
+macro_rules! storage_formats {
+    ($format:expr, $($name:ident),* $(,)?) => {
+        match $format {
+            $(naga::StorageFormat::$name => wgpu::TextureFormat::$name,)*
+        }
+    };
+}
+
+fn storage_format(format: naga::StorageFormat) -> wgpu::TextureFormat {
+    storage_formats!(
+        format,
+        R8Unorm,
+        R8Snorm,
+        R8Uint,
+        R8Sint,
+        R16Uint,
+        R16Sint,
+        R16Float,
+        Rg8Unorm,
+        Rg8Snorm,
+        Rg8Uint,
+        Rg8Sint,
+        R32Uint,
+        R32Sint,
+        R32Float,
+        Rg16Uint,
+        Rg16Sint,
+        Rg16Float,
+        Rgba8Unorm,
+        Rgba8Snorm,
+        Rgba8Uint,
+        Rgba8Sint,
+        Bgra8Unorm,
+        Rgb10a2Uint,
+        Rgb10a2Unorm,
+        Rg11b10Ufloat,
+        R64Uint,
+        Rg32Uint,
+        Rg32Sint,
+        Rg32Float,
+        Rgba16Uint,
+        Rgba16Sint,
+        Rgba16Float,
+        Rgba32Uint,
+        Rgba32Sint,
+        Rgba32Float,
+        R16Unorm,
+        R16Snorm,
+        Rg16Unorm,
+        Rg16Snorm,
+        Rgba16Unorm,
+        Rgba16Snorm,
+    )
+}
+
+/// Whether a layout entry of type `layout` can be bound to a resource the
+/// shader declares as `shader`, following the rules wgpu checks at pipeline
+/// creation
+fn compatible(shader: &wgpu::BindingType, layout: &wgpu::BindingType) -> bool {
+    use wgpu::{BindingType, SamplerBindingType, TextureSampleType};
+
+    match (shader, layout) {
+        (
+            BindingType::Buffer {
+                ty: shader_ty,
+                min_binding_size: shader_size,
+                ..
+            },
+            BindingType::Buffer {
+                ty: layout_ty,
+                min_binding_size: layout_size,
+                ..
+            },
+        ) => {
+            shader_ty == layout_ty
+                && match (shader_size, layout_size) {
+                    (Some(shader_size), Some(layout_size)) => layout_size >= shader_size,
+                    _ => true,
+                }
+        }
+        (
+            BindingType::Texture {
+                sample_type: shader_sample,
+                view_dimension: shader_dimension,
+                multisampled: shader_multisampled,
+            },
+            BindingType::Texture {
+                sample_type: layout_sample,
+                view_dimension: layout_dimension,
+                multisampled: layout_multisampled,
+            },
+        ) => {
+            shader_dimension == layout_dimension
+                && shader_multisampled == layout_multisampled
+                && match (shader_sample, layout_sample) {
+                    (
+                        TextureSampleType::Float { .. },
+                        TextureSampleType::Float { .. } | TextureSampleType::Depth,
+                    ) => true,
+                    (shader_sample, layout_sample) => shader_sample == layout_sample,
+                }
+        }
+        (BindingType::StorageTexture { .. }, BindingType::StorageTexture { .. }) => {
+            shader == layout
+        }
+        (BindingType::Sampler(shader), BindingType::Sampler(layout)) => {
+            (*shader == SamplerBindingType::Comparison)
+                == (*layout == SamplerBindingType::Comparison)
+        }
+        (BindingType::ExternalTexture, BindingType::ExternalTexture) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod reflection_tests {
+    use crate::prelude::{core::*, *};
+
+    const SHADER: &str = "
+        struct CameraUniform {
+            view_proj: mat4x4<f32>,
+        };
+
+        @group(0) @binding(0)
+        var<uniform> camera: CameraUniform;
+
+        @group(1) @binding(0)
+        var render: texture_2d<f32>;
+        @group(1) @binding(1)
+        var render_sampler: sampler;
+
+        @vertex
+        fn vs(@location(0) position: vec3<f32>) -> @builtin(position) vec4<f32> {
+            return camera.view_proj * vec4<f32>(position, 1.0);
+        }
+
+        @fragment
+        fn fs(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+            return textureSample(render, render_sampler, position.xy);
+        }
+    ";
+
+    #[test]
+    fn test_reflect_bindings() {
+        let reflection = ShaderReflection::from_wgsl(SHADER).unwrap();
+
+        assert_eq!(reflection.group_count(), 2);
+
+        let camera = &reflection.bindings()[0];
+        assert_eq!(camera.name.as_deref(), Some("camera"));
+        assert_eq!(camera.visibility, wgpu::ShaderStages::VERTEX);
+        assert_eq!(
+            camera.ty,
+            wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: std::num::NonZeroU64::new(64),
+            }
+        );
+
+        assert_eq!(reflection.layout_entries(1).len(), 2);
+    }
+
+    #[test]
+    fn test_validate_bind_layouts() {
+        let reflection = ShaderReflection::from_wgsl(SHADER).unwrap();
+
+        reflection
+            .validate(&[&CameraBindLayout::entries(), &PostProcBindLayout::entries()])
+            .unwrap();
+    }
+
+    #[test]
+    fn test_validate_mismatches() {
+        let reflection = ShaderReflection::from_wgsl(SHADER).unwrap();
+
+        let Err(ReflectionError::Mismatch(mismatches)) =
+            reflection.validate_bind_layout::<PostProcBindLayout>(0)
+        else {
+            panic!("expected the layouts to mismatch");
+        };
+
+        assert_eq!(mismatches.len(), 2);
+        assert!(matches!(mismatches[0].kind, MismatchKind::Type { .. }));
+        assert!(matches!(
+            mismatches[1].kind,
+            MismatchKind::Visibility { .. }
+        ));
+
+        let Err(ReflectionError::Mismatch(mismatches)) =
+            reflection.validate(&[&[wgpu::BindGroupLayoutEntry {
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ..CameraBindLayout::entries()[0]
+            }]])
+        else {
+            panic!("expected the layouts to mismatch");
+        };
+
+        assert!(matches!(
+            mismatches[0].kind,
+            MismatchKind::Visibility { .. }
+        ));
+        assert_eq!(mismatches[1].kind, MismatchKind::Missing);
+        assert_eq!(mismatches[2].kind, MismatchKind::Missing);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = ShaderReflection::from_wgsl("@group(0) @binding(0) var<uniform> x: f3;");
+
+        assert!(matches!(error, Err(ReflectionError::Parse(_))));
+    }
+}
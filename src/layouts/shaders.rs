@@ -1,2 +1,3 @@
 pub mod compute_shader;
 pub mod shader;
+pub mod variants;
@@ -8,6 +8,13 @@ pub trait Shader {
 
     fn get_pipeline(&self) -> &wgpu::RenderPipeline;
 
+    /// Pipeline used when the shader is applied together with its settings,
+    /// such as a variant from `PipelineVariants`
+    #[allow(unused)]
+    fn get_pipeline_with(&self, settings: &Self::Settings) -> &wgpu::RenderPipeline {
+        self.get_pipeline()
+    }
+
     #[allow(unused)]
     fn apply_settings(render_pass: &mut wgpu::RenderPass, settings: &Self::Settings) {}
 }
@@ -20,6 +27,10 @@ impl<S: Shader> Shader for Rc<S> {
         self.as_ref().get_pipeline()
     }
 
+    fn get_pipeline_with(&self, settings: &Self::Settings) -> &wgpu::RenderPipeline {
+        self.as_ref().get_pipeline_with(settings)
+    }
+
     fn apply_settings(render_pass: &mut wgpu::RenderPass, settings: &Self::Settings) {
         S::apply_settings(render_pass, settings)
     }
@@ -33,6 +44,10 @@ impl<S: Shader> Shader for Arc<S> {
         self.as_ref().get_pipeline()
     }
 
+    fn get_pipeline_with(&self, settings: &Self::Settings) -> &wgpu::RenderPipeline {
+        self.as_ref().get_pipeline_with(settings)
+    }
+
     fn apply_settings(render_pass: &mut wgpu::RenderPass, settings: &Self::Settings) {
         S::apply_settings(render_pass, settings)
     }
@@ -46,6 +61,10 @@ impl<S: Shader> Shader for Box<S> {
         self.as_ref().get_pipeline()
     }
 
+    fn get_pipeline_with(&self, settings: &Self::Settings) -> &wgpu::RenderPipeline {
+        self.as_ref().get_pipeline_with(settings)
+    }
+
     fn apply_settings(render_pass: &mut wgpu::RenderPass, settings: &Self::Settings) {
         S::apply_settings(render_pass, settings)
     }
@@ -59,6 +78,10 @@ impl<S: Shader> Shader for Sc<S> {
         self.as_ref().get_pipeline()
     }
 
+    fn get_pipeline_with(&self, settings: &Self::Settings) -> &wgpu::RenderPipeline {
+        self.as_ref().get_pipeline_with(settings)
+    }
+
     fn apply_settings(render_pass: &mut wgpu::RenderPass, settings: &Self::Settings) {
         S::apply_settings(render_pass, settings)
     }
@@ -72,6 +95,10 @@ impl<S: Shader> Shader for Asc<S> {
         self.as_ref().get_pipeline()
     }
 
+    fn get_pipeline_with(&self, settings: &Self::Settings) -> &wgpu::RenderPipeline {
+        self.as_ref().get_pipeline_with(settings)
+    }
+
     fn apply_settings(render_pass: &mut wgpu::RenderPass, settings: &Self::Settings) {
         S::apply_settings(render_pass, settings)
     }
@@ -0,0 +1,294 @@
+use std::{collections::HashMap, fmt::Debug, hash::Hash};
+
+use crate::prelude::*;
+
+/// Settings that select a pipeline variant, specialized through the
+/// pipeline-overridable constants of the shader module
+///
+/// ```wgsl
+/// override SAMPLES: u32 = 4u;
+/// @id(0) override SHADOWS: bool = true;
+/// ```
+pub trait VariantKey: Clone + Eq + Hash {
+    /// Values for the `override` declarations of the shader, keyed by their
+    /// identifier or by their `@id` as a decimal number
+    fn constants(&self) -> Vec<(&'static str, f64)>;
+}
+
+type BuildPipeline<P> = dyn Fn(&[(&str, f64)]) -> P + Send + Sync;
+
+/// Pipelines of a single shader module built lazily, one for each settings
+/// key they are requested with, to back `Shader::get_pipeline_with` or
+/// `ComputeShader::get_compute_pipeline`
+pub struct PipelineVariants<K, P> {
+    build: Box<BuildPipeline<P>>,
+    // boxed so that references handed out stay valid as the map grows
+    variants: RwLock<HashMap<K, Box<P>>>,
+}
+
+impl<K: VariantKey, P> PipelineVariants<K, P> {
+    /// Variants built by `build` from the constants of each key
+    #[inline]
+    pub fn new(build: impl Fn(&[(&str, f64)]) -> P + Send + Sync + 'static) -> Self {
+        Self {
+            build: Box::new(build),
+            variants: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Pipeline for `key`, building it the first time it is requested
+    pub fn get(&self, key: &K) -> &P {
+        if let Some(variant) = self.variants.read().unwrap().get(key) {
+            // SAFETY: variants are only removed through `&mut self`
+            return unsafe { &*(&**variant as *const P) };
+        }
+
+        // built without holding the lock, so readers of other variants are not
+        // blocked and a panicking build leaves the lock unpoisoned
+        let variant = Box::new((self.build)(&key.constants()));
+
+        let mut variants = self.variants.write().unwrap();
+
+        // another thread may have built the same variant meanwhile
+        let variant = variants.entry(key.clone()).or_insert(variant);
+
+        // SAFETY: variants are only removed through `&mut self`
+        unsafe { &*(&**variant as *const P) }
+    }
+
+    /// Builds the variants for `keys` ahead of time to avoid a stall on the
+    /// frame they are first used
+    pub fn prepare(&self, keys: impl IntoIterator<Item = K>) {
+        for key in keys {
+            self.get(&key);
+        }
+    }
+
+    #[inline]
+    pub fn contains(&self, key: &K) -> bool {
+        self.variants.read().unwrap().contains_key(key)
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.variants.read().unwrap().len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops every variant built so far, such as after the shader source
+    /// has been reloaded
+    #[inline]
+    pub fn clear(&mut self) {
+        self.variants.get_mut().unwrap().clear();
+    }
+}
+
+impl<K: VariantKey> PipelineVariants<K, wgpu::RenderPipeline> {
+    pub fn render<V>(
+        render_context: &RenderContext,
+        layout: &RawLayout<V>,
+        module: wgpu::ShaderModule,
+        shader_config: ShaderConfig<'static>,
+    ) -> Self
+    where
+        V: VertexBufferLayout + Send + Sync + 'static,
+    {
+        let render_context = render_context.clone();
+        let layout = layout.clone();
+
+        Self::new(move |constants| {
+            let compilation_options = shader_config.compilation_options.clone();
+
+            layout.create_pipeline(
+                &render_context,
+                &module,
+                ShaderConfig {
+                    compilation_options: Some(wgpu::PipelineCompilationOptions {
+                        constants,
+                        ..compilation_options.unwrap_or_default()
+                    }),
+                    ..shader_config.clone()
+                },
+            )
+        })
+    }
+}
+
+impl<K: VariantKey> PipelineVariants<K, wgpu::ComputePipeline> {
+    pub fn compute(
+        render_context: &RenderContext,
+        layout: &RawComputeLayout,
+        module: wgpu::ShaderModule,
+        compute_shader_config: ComputeShaderConfig<'static>,
+    ) -> Self {
+        let render_context = render_context.clone();
+        let layout = layout.clone();
+
+        Self::new(move |constants| {
+            let compilation_options = compute_shader_config.compilation_options.clone();
+
+            layout.create_compute_pipeline(
+                &render_context,
+                &module,
+                ComputeShaderConfig {
+                    compilation_options: Some(wgpu::PipelineCompilationOptions {
+                        constants,
+                        ..compilation_options.unwrap_or_default()
+                    }),
+                    ..compute_shader_config.clone()
+                },
+            )
+        })
+    }
+}
+
+impl<K: Debug, P> Debug for PipelineVariants<K, P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PipelineVariants")
+            .field("variants", &self.variants.read().unwrap().keys())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod variants_tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::prelude::*;
+
+    create_bind::bind!(OutputBind, OutputBindLayout {
+        StorageBuffers => {
+            output: f32 => 0 for COMPUTE use {
+                read_only: false,
+            },
+        },
+    });
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    struct Scale(u32);
+
+    impl VariantKey for Scale {
+        fn constants(&self) -> Vec<(&'static str, f64)> {
+            vec![("SCALE", self.0 as f64)]
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    struct Quality {
+        samples: u32,
+        shadows: bool,
+    }
+
+    impl VariantKey for Quality {
+        fn constants(&self) -> Vec<(&'static str, f64)> {
+            vec![
+                ("SAMPLES", self.samples as f64),
+                ("0", self.shadows as u32 as f64),
+            ]
+        }
+    }
+
+    #[test]
+    fn test_variants_built_once() {
+        let builds = Arc::new(AtomicUsize::new(0));
+
+        let variants = PipelineVariants::<Quality, Vec<(String, f64)>>::new({
+            let builds = builds.clone();
+            move |constants| {
+                builds.fetch_add(1, Ordering::Relaxed);
+                constants
+                    .iter()
+                    .map(|&(name, value)| (name.to_owned(), value))
+                    .collect()
+            }
+        });
+
+        let low = Quality {
+            samples: 1,
+            shadows: false,
+        };
+        let high = Quality {
+            samples: 8,
+            shadows: true,
+        };
+
+        assert!(variants.is_empty());
+
+        let first = variants.get(&high);
+        assert_eq!(first, &[("SAMPLES".to_owned(), 8.0), ("0".to_owned(), 1.0)]);
+
+        variants.prepare([low, high]);
+        assert_eq!(variants.len(), 2);
+        assert_eq!(builds.load(Ordering::Relaxed), 2);
+
+        assert!(std::ptr::eq(first, variants.get(&high)));
+    }
+
+    #[tokio::test]
+    async fn test_override_variants() {
+        let render_context = RenderContext::new(RenderContextConfig::default()).await;
+
+        let bind_layout = OutputBindLayout::new(&render_context);
+
+        let layout = RawComputeLayout::new(
+            &render_context,
+            ComputeLayoutConfig {
+                label: None,
+                bind_group_layouts: &[bind_layout.wgpu_layout()],
+                push_constant_ranges: &[],
+            },
+        );
+
+        let module = render_context.create_shader_module(
+            None,
+            wgpu::ShaderSource::Wgsl(
+                "
+                override SCALE: f32 = 1.0;
+
+                @group(0) @binding(0) var<storage, read_write> output: f32;
+
+                @compute @workgroup_size(1)
+                fn cs() {
+                    output = SCALE * 2.0;
+                }
+                "
+                .into(),
+            ),
+        );
+
+        let variants = PipelineVariants::<Scale, wgpu::ComputePipeline>::compute(
+            &render_context,
+            &layout,
+            module,
+            ComputeShaderConfig::default(),
+        );
+
+        let outputs = [Scale(1), Scale(3)].map(|key| {
+            let bind = OutputBind::new(
+                &render_context,
+                bind_layout.clone(),
+                StorageBuffer::new_init(&render_context, None, &[0.0]),
+            );
+
+            let mut encoder = render_context.command_encoder();
+            {
+                let mut compute_pass = encoder.compute_pass();
+                let compute_pass = unsafe { compute_pass.inner() };
+
+                compute_pass.set_pipeline(variants.get(&key));
+                compute_pass.set_bind_group(0, bind.bind_group(), &[]);
+                compute_pass.dispatch_workgroups(1, 1, 1);
+            }
+            encoder.submit();
+
+            bind.output().read_blocking(&render_context)
+        });
+
+        assert_eq!(variants.len(), 2);
+        assert_eq!(outputs, [[2.0], [6.0]]);
+    }
+}
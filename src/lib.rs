@@ -1,5 +1,6 @@
 #![feature(associated_type_defaults, negative_impls, auto_traits)]
 
+pub(crate) mod error;
 pub(crate) mod handler;
 pub(crate) mod render_context;
 
@@ -1,14 +1,24 @@
 pub use crate::{
     bind::{
-        create_bind, dynamic_buffer::DynamicBuffer, storage_buffer::StorageBuffer,
-        uniform_buffer::UniformBuffer, Bind, BindLayout,
+        create_bind,
+        dynamic_buffer::DynamicBuffer,
+        dynamic_uniform_buffer::DynamicUniformBuffer,
+        storage_buffer::StorageBuffer,
+        texture_registry::{TextureRegistry, TextureRegistryLayout},
+        uniform_buffer::UniformBuffer,
+        wgsl_layout::{create_wgsl_layout, StorageData, UniformData, WgslLayout},
+        Bind, BindLayout, BindLayoutEntries,
     },
+    error::{CapacityError, ComposeError, InitError, ReflectionError, ValidationError},
     handler::{
         app::{App, AppConfig, Framerate},
-        window::{Window, WindowCommandEncoder, WindowConfig},
-        Callbacks, Handler, OnCloseCallBack, OnEventCallback, OnPollCallback, OnStartCallback,
+        frames_in_flight::FramesInFlight,
+        window::{SurfaceEvent, Window, WindowCommandEncoder, WindowConfig},
+        Callbacks, Handler, OnCloseCallBack, OnDrawCallback, OnEventCallback, OnPollCallback,
+        OnStartCallback, WithSurfaceEvent,
     },
     layouts::{
+        composer::{ComposedShader, ShaderComposer, ShaderLocation},
         compute_layout::{
             ComputeLayout, ComputeLayoutConfig, ComputeShaderConfig, CreateComputePipeline,
             RawComputeLayout, SharedComputeData,
@@ -19,14 +29,18 @@ pub use crate::{
             SimpleInstances5,
         },
         layout::{
-            CreatePipeline, Layout, LayoutConfig, RawLayout, ShaderConfig, SharedData, VertexLayout,
+            BlendMode, CreatePipeline, Layout, LayoutConfig, RawLayout, ShaderConfig, SharedData,
+            VertexLayout,
         },
+        push_constants::{PushConstantLayout, PushConstants},
+        reflection::{BindingMismatch, MismatchKind, ReflectedBinding, ShaderReflection},
         shaders::{
             compute_shader::{
                 ApplyComputeShaderInstance, ComputeShader, ComputeShaderHandle,
                 ComputeShaderInstance, DefaultComputeShaderInstance, StaticComputeShaderInstance,
             },
             shader::Shader,
+            variants::{PipelineVariants, VariantKey},
         },
         vertex::{
             create_vertex_attr, create_vertex_layout, IRequirements, InstanceRequirements,
@@ -36,7 +50,9 @@ pub use crate::{
     render_context::{
         command_encoder::CommandEncoder,
         compute_pass::ComputePass,
+        profiler::Profiler,
         render_pass::{render_pass_mut::RenderPassMut, Instanced, RenderPass},
+        render_target::{RenderTarget, RenderTargetConfig},
         RenderContext, RenderContextConfig,
     },
     surface::mesh::{
@@ -44,8 +60,8 @@ pub use crate::{
         SimpleMesh5,
     },
     texture::{
-        RawTexture, RawTextureView, Sampler, Texture, Texture1D, Texture2D, Texture3D,
-        TextureConfig,
+        RawTexture, RawTextureView, Sampler, Texture, Texture1D, Texture2D, Texture2DArray,
+        Texture3D, TextureConfig, TextureCube, TextureCubeArray, TextureDimension,
     },
 };
 
@@ -71,6 +87,7 @@ pub mod core {
         post_processing::{PostProc, PostProcBind, PostProcBindLayout},
         tileset::{SimpleTileLayout, TileInstance, TilesetQuad},
         transform::{Transform, Transform2d},
+        transparent_queue::TransparentQueue,
         vertex::{PosVertex, PosVertex2d, RBGAVertex, RGBVertex, UVVertex},
     };
 }
@@ -1,16 +1,24 @@
+use std::{fs, io, path::PathBuf};
+
 use crate::prelude::*;
 
 pub mod command_encoder;
 pub mod compute_pass;
+pub mod profiler;
 pub mod render_pass;
+pub mod render_target;
 
 #[derive(Default, Debug, Clone)]
 pub struct RenderContextConfig {
     pub backends: Option<wgpu::Backends>,
     pub power_preference: Option<wgpu::PowerPreference>,
+    pub force_fallback_adapter: bool,
     pub features: Option<wgpu::Features>,
     pub experimental_features: wgpu::ExperimentalFeatures,
     pub limits: Option<wgpu::Limits>,
+    /// File the pipeline cache is loaded from and saved to, only used
+    /// when `Features::PIPELINE_CACHE` is enabled
+    pub pipeline_cache: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone)]
@@ -19,6 +27,7 @@ pub struct RenderContext {
     adapter: wgpu::Adapter,
     device: wgpu::Device,
     queue: wgpu::Queue,
+    pipeline_cache: Option<(wgpu::PipelineCache, PathBuf)>,
 }
 
 impl RenderContext {
@@ -46,7 +55,14 @@ impl RenderContext {
         &self.queue
     }
 
+    #[inline]
     pub async fn new(config: RenderContextConfig) -> Self {
+        Self::try_new(config)
+            .await
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    pub async fn try_new(config: RenderContextConfig) -> Result<Self, InitError> {
         let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
             backends: config.backends.unwrap_or(wgpu::Backends::PRIMARY),
             ..Default::default()
@@ -58,31 +74,106 @@ impl RenderContext {
                     .power_preference
                     .unwrap_or(wgpu::PowerPreference::HighPerformance),
                 compatible_surface: None,
-                force_fallback_adapter: false,
+                force_fallback_adapter: config.force_fallback_adapter,
             })
             .await
-            .unwrap();
+            .map_err(InitError::NoAdapter)?;
+
+        let features = config.features.unwrap_or(wgpu::Features::empty());
+        let limits = config.limits.unwrap_or_default();
 
         let (device, queue) = adapter
             .request_device(&wgpu::DeviceDescriptor {
-                required_features: config.features.unwrap_or(wgpu::Features::empty()),
-                required_limits: config.limits.unwrap_or_default(),
+                required_features: features,
+                required_limits: limits.clone(),
                 label: Some("Device"),
                 memory_hints: wgpu::MemoryHints::default(),
                 trace: wgpu::Trace::Off,
                 experimental_features: config.experimental_features,
             })
             .await
-            .unwrap();
+            .map_err(|error| InitError::RequestDevice {
+                error,
+                features,
+                limits: Box::new(limits),
+            })?;
+
+        let pipeline_cache = config
+            .pipeline_cache
+            .filter(|_| device.features().contains(wgpu::Features::PIPELINE_CACHE))
+            .map(|path| {
+                let data = fs::read(&path).ok();
 
-        Self {
+                // Data that does not match the adapter is rejected by
+                // wgpu's header validation, falling back to an empty cache
+                let cache = unsafe {
+                    device.create_pipeline_cache(&wgpu::PipelineCacheDescriptor {
+                        label: Some("Pipeline Cache"),
+                        data: data.as_deref(),
+                        fallback: true,
+                    })
+                };
+
+                (cache, path)
+            });
+
+        Ok(Self {
             instance,
             adapter,
             device,
             queue,
+            pipeline_cache,
+        })
+    }
+
+    #[inline]
+    pub fn pipeline_cache(&self) -> Option<&wgpu::PipelineCache> {
+        self.pipeline_cache.as_ref().map(|(cache, _)| cache)
+    }
+
+    /// Writes the pipeline cache back to the file it was loaded from,
+    /// this is also done by the `Handler` when the window is closed
+    pub fn save_pipeline_cache(&self) -> io::Result<()> {
+        let Some((cache, path)) = &self.pipeline_cache else {
+            return Ok(());
+        };
+
+        let Some(data) = cache.get_data() else {
+            return Ok(());
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let temp = path.with_extension("tmp");
+        fs::write(&temp, data)?;
+        fs::rename(temp, path)
+    }
+
+    /// Runs `create` inside a validation error scope, returning
+    /// the first validation error it caused instead of the value
+    pub async fn capture_validation<T>(
+        &self,
+        create: impl FnOnce() -> T,
+    ) -> Result<T, ValidationError> {
+        self.device.push_error_scope(wgpu::ErrorFilter::Validation);
+
+        let value = create();
+
+        match self.device.pop_error_scope().await {
+            Some(error) => Err(ValidationError { error }),
+            None => Ok(value),
         }
     }
 
+    /// Routes every error that is not captured by an error scope to
+    /// `handler`, instead of wgpu's default handler which panics
+    #[inline]
+    pub fn on_uncaptured_error(&self, handler: impl Fn(wgpu::Error) + Send + Sync + 'static) {
+        self.device.on_uncaptured_error(Arc::new(handler));
+    }
+
     #[inline]
     pub fn create_shader_module(
         &self,
@@ -93,6 +184,16 @@ impl RenderContext {
             .create_shader_module(wgpu::ShaderModuleDescriptor { label, source })
     }
 
+    #[inline]
+    pub async fn create_shader_module_checked(
+        &self,
+        label: Option<&str>,
+        source: wgpu::ShaderSource<'_>,
+    ) -> Result<wgpu::ShaderModule, ValidationError> {
+        self.capture_validation(|| self.create_shader_module(label, source))
+            .await
+    }
+
     pub fn command_encoder(&'_ self) -> CommandEncoder<'_> {
         let encoder = self
             .device
@@ -103,6 +204,80 @@ impl RenderContext {
         CommandEncoder {
             encoder,
             render_context: self,
+            profiler: None,
+            pass_label: None,
         }
     }
 }
+
+#[cfg(test)]
+mod render_context_tests {
+    use std::{fs, path::PathBuf};
+
+    use crate::prelude::*;
+
+    fn cache_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("r3_{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_save_without_pipeline_cache() {
+        let dir = cache_dir("no_pipeline_cache");
+        let path = dir.join("pipelines.bin");
+
+        let render_context = RenderContext::new(RenderContextConfig {
+            pipeline_cache: Some(path.clone()),
+            ..default()
+        })
+        .await;
+
+        assert!(render_context.pipeline_cache().is_none());
+        assert!(render_context.save_pipeline_cache().is_ok());
+        assert!(!dir.exists());
+    }
+
+    #[tokio::test]
+    async fn test_save_pipeline_cache() {
+        let dir = cache_dir("pipeline_cache");
+        let path = dir.join("pipelines.bin");
+
+        let render_context = match RenderContext::try_new(RenderContextConfig {
+            features: Some(wgpu::Features::PIPELINE_CACHE),
+            pipeline_cache: Some(path.clone()),
+            ..default()
+        })
+        .await
+        {
+            Ok(render_context) => render_context,
+            // the adapter has no pipeline cache, which is covered above
+            Err(InitError::RequestDevice { .. }) => return,
+            Err(error) => panic!("{error}"),
+        };
+
+        assert!(render_context.pipeline_cache().is_some());
+
+        // fills the cache with a pipeline to save
+        let module = render_context.create_shader_module(
+            None,
+            wgpu::ShaderSource::Wgsl("@compute @workgroup_size(1) fn cs() {}".into()),
+        );
+        RawComputeLayout::new(
+            &render_context,
+            ComputeLayoutConfig {
+                label: None,
+                bind_group_layouts: &[],
+                push_constant_ranges: &[],
+            },
+        )
+        .create_compute_pipeline(&render_context, &module, default());
+
+        render_context.save_pipeline_cache().unwrap();
+
+        assert!(path.exists());
+        assert!(!path.with_extension("tmp").exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
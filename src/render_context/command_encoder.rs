@@ -4,27 +4,100 @@ use crate::prelude::*;
 pub struct CommandEncoder<'r> {
     pub encoder: wgpu::CommandEncoder,
     pub render_context: &'r RenderContext,
+    pub(crate) profiler: Option<&'r mut Profiler>,
+    pub(crate) pass_label: Option<String>,
+}
+
+impl<'r> CommandEncoder<'r> {
+    /// Times every following pass with `profiler`, the results are
+    /// available from the profiler once the frame has completed
+    pub fn with_profiler<'p>(self, profiler: &'p mut Profiler) -> CommandEncoder<'p>
+    where
+        'r: 'p,
+    {
+        profiler.begin_frame(self.render_context);
+
+        CommandEncoder {
+            encoder: self.encoder,
+            render_context: self.render_context,
+            profiler: Some(profiler),
+            pass_label: self.pass_label,
+        }
+    }
+
+    /// Sets the label used for the next render or compute pass,
+    /// both for debugging and for profiling
+    #[inline]
+    pub fn set_pass_label(&mut self, label: impl Into<String>) -> &mut Self {
+        self.pass_label = Some(label.into());
+        self
+    }
 }
 
 impl CommandEncoder<'_> {
+    #[inline]
     pub fn render_pass(
         &'_ mut self,
         view: &RawTextureView<Texture2D>,
         load: Option<wgpu::LoadOp<wgpu::Color>>,
         depth_stencil_attachment: Option<wgpu::RenderPassDepthStencilAttachment>,
     ) -> RenderPass<'_> {
+        self.render_pass_targets(&[view], load, depth_stencil_attachment)
+    }
+
+    /// Begins a render pass writing to every view in `views`, one for each
+    /// color target of the layout and in the same order, all loaded with `load`
+    pub fn render_pass_targets(
+        &'_ mut self,
+        views: &[&RawTextureView<Texture2D>],
+        load: Option<wgpu::LoadOp<wgpu::Color>>,
+        depth_stencil_attachment: Option<wgpu::RenderPassDepthStencilAttachment>,
+    ) -> RenderPass<'_> {
+        let ops = wgpu::Operations {
+            load: load.unwrap_or(wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT)),
+            store: wgpu::StoreOp::Store,
+        };
+
+        let color_attachments: Vec<_> = views
+            .iter()
+            .map(|view| {
+                Some(wgpu::RenderPassColorAttachment {
+                    view: view.inner(),
+                    resolve_target: None,
+                    ops,
+                    depth_slice: None,
+                })
+            })
+            .collect();
+
+        self.render_pass_attachments(&color_attachments, depth_stencil_attachment)
+    }
+
+    /// Begins a render pass with full control over each color attachment,
+    /// such as to load or store each target differently
+    pub fn render_pass_attachments(
+        &'_ mut self,
+        color_attachments: &[Option<wgpu::RenderPassColorAttachment>],
+        depth_stencil_attachment: Option<wgpu::RenderPassDepthStencilAttachment>,
+    ) -> RenderPass<'_> {
+        let label = self.pass_label.take();
+        let label = label.as_deref().unwrap_or("Render Pass");
+
+        let timestamp_writes = self
+            .profiler
+            .as_deref_mut()
+            .and_then(|profiler| profiler.begin_pass(label))
+            .map(|(query_set, index)| wgpu::RenderPassTimestampWrites {
+                query_set,
+                beginning_of_pass_write_index: Some(index),
+                end_of_pass_write_index: Some(index + 1),
+            });
+
         let render_pass = self.encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-            label: Some("Render Pass"),
-            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                view,
-                resolve_target: None,
-                ops: wgpu::Operations {
-                    load: load.unwrap_or(wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT)),
-                    store: wgpu::StoreOp::Store,
-                },
-                depth_slice: None,
-            })],
+            label: Some(label),
+            color_attachments,
             depth_stencil_attachment,
+            timestamp_writes,
             ..Default::default()
         });
 
@@ -33,25 +106,80 @@ impl CommandEncoder<'_> {
             __layout: PhantomData,
             __shader_attached: PhantomData,
             instance: Void,
+            timer: self
+                .profiler
+                .as_deref_mut()
+                .and_then(|profiler| profiler.cpu_timer()),
         }
     }
 
+    pub fn render_target_pass(
+        &'_ mut self,
+        target: &RenderTarget,
+        load: Option<wgpu::LoadOp<wgpu::Color>>,
+        depth_stencil_attachment: bool,
+    ) -> RenderPass<'_> {
+        self.render_pass(
+            unsafe { target.texture.view() },
+            Some(load.unwrap_or(wgpu::LoadOp::Clear(
+                target.clear.unwrap_or(wgpu::Color::TRANSPARENT),
+            ))),
+            if depth_stencil_attachment {
+                Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: unsafe { target.depth_texture.texture.view() }.inner(),
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: wgpu::StoreOp::Store,
+                    }),
+                    stencil_ops: None,
+                })
+            } else {
+                None
+            },
+        )
+    }
+
     pub fn compute_pass(&mut self) -> ComputePass<'_, Void> {
+        let label = self.pass_label.take();
+        let label = label.as_deref().unwrap_or("Compute Pass");
+
+        let timestamp_writes = self
+            .profiler
+            .as_deref_mut()
+            .and_then(|profiler| profiler.begin_pass(label))
+            .map(|(query_set, index)| wgpu::ComputePassTimestampWrites {
+                query_set,
+                beginning_of_pass_write_index: Some(index),
+                end_of_pass_write_index: Some(index + 1),
+            });
+
         ComputePass {
             compute_pass: self
                 .encoder
                 .begin_compute_pass(&wgpu::ComputePassDescriptor {
-                    label: Some("Compute Pass"),
-                    ..Default::default()
+                    label: Some(label),
+                    timestamp_writes,
                 }),
             __layout: PhantomData,
+            timer: self
+                .profiler
+                .as_deref_mut()
+                .and_then(|profiler| profiler.cpu_timer()),
         }
     }
 
     #[inline]
-    pub fn submit(self) {
+    pub fn submit(mut self) {
+        if let Some(profiler) = self.profiler.as_deref_mut() {
+            profiler.resolve(&mut self.encoder);
+        }
+
         self.render_context
             .queue
             .submit(Some(self.encoder.finish()));
+
+        if let Some(profiler) = self.profiler {
+            profiler.after_submit();
+        }
     }
 }
@@ -1,10 +1,10 @@
-use crate::prelude::*;
+use crate::{prelude::*, render_context::profiler::PassTimer};
 
-#[repr(transparent)]
 #[derive(Debug)]
 pub struct ComputePass<'r, Layout> {
     pub(crate) compute_pass: wgpu::ComputePass<'r>,
     pub(crate) __layout: PhantomData<Layout>,
+    pub(crate) timer: Option<PassTimer<'r>>,
 }
 
 impl<'r, L> ComputePass<'r, L> {
@@ -23,6 +23,7 @@ impl<'r, L> ComputePass<'r, L> {
         ComputePass {
             compute_pass: self.compute_pass,
             __layout: PhantomData,
+            timer: self.timer,
         }
     }
 
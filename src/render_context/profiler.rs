@@ -0,0 +1,330 @@
+use std::{
+    ops::Range,
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+use crate::prelude::*;
+
+/// Records how long each labelled pass of a `CommandEncoder` takes
+///
+/// When the device was created with `Features::TIMESTAMP_QUERY` every pass
+/// is wrapped with GPU timestamp queries, which are resolved at `submit` and
+/// read back once the GPU has finished the frame. Otherwise only the CPU time
+/// spent encoding each pass is recorded, from when the pass begins to when
+/// the `RenderPass` or `ComputePass` is dropped
+///
+/// Only one frame of GPU timings is read back at a time, frames submitted
+/// while the previous readback is still pending are not timed and are
+/// counted by `dropped_frames` instead
+#[derive(Debug)]
+pub struct Profiler {
+    timestamps: Option<Timestamps>,
+    passes: Vec<(String, Range<Instant>)>,
+    last_frame: Vec<(String, Duration)>,
+    dropped_frames: u64,
+}
+
+type MapReceiver = mpsc::Receiver<Result<(), wgpu::BufferAsyncError>>;
+
+#[derive(Debug)]
+struct Timestamps {
+    query_set: wgpu::QuerySet,
+    resolve_buffer: wgpu::Buffer,
+    readback_buffer: wgpu::Buffer,
+    max_passes: u32,
+    period: f32,
+    pending: Option<(Vec<String>, MapReceiver)>,
+}
+
+impl Profiler {
+    pub fn new(render_context: &RenderContext, max_passes: u32) -> Self {
+        let device = unsafe { render_context.device() };
+
+        let timestamps = device
+            .features()
+            .contains(wgpu::Features::TIMESTAMP_QUERY)
+            .then(|| {
+                let size = max_passes as u64 * 2 * wgpu::QUERY_SIZE as u64;
+
+                Timestamps {
+                    query_set: device.create_query_set(&wgpu::QuerySetDescriptor {
+                        label: Some("Profiler Query Set"),
+                        ty: wgpu::QueryType::Timestamp,
+                        count: max_passes * 2,
+                    }),
+                    resolve_buffer: device.create_buffer(&wgpu::BufferDescriptor {
+                        label: Some("Profiler Resolve Buffer"),
+                        usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+                        size,
+                        mapped_at_creation: false,
+                    }),
+                    readback_buffer: device.create_buffer(&wgpu::BufferDescriptor {
+                        label: Some("Profiler Readback Buffer"),
+                        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+                        size,
+                        mapped_at_creation: false,
+                    }),
+                    max_passes,
+                    period: unsafe { render_context.queue() }.get_timestamp_period(),
+                    pending: None,
+                }
+            });
+
+        Self {
+            timestamps,
+            passes: Vec::new(),
+            last_frame: Vec::new(),
+            dropped_frames: 0,
+        }
+    }
+
+    /// Always times passes by encode time, even when the device supports
+    /// timestamp queries
+    #[inline]
+    pub const fn new_fallback() -> Self {
+        Self {
+            timestamps: None,
+            passes: Vec::new(),
+            last_frame: Vec::new(),
+            dropped_frames: 0,
+        }
+    }
+
+    /// Whether passes are timed on the GPU rather than by encode time
+    #[inline(always)]
+    pub const fn is_gpu(&self) -> bool {
+        self.timestamps.is_some()
+    }
+
+    /// Per-label durations of the last frame whose timings are available
+    #[inline(always)]
+    pub fn last_frame(&self) -> &[(String, Duration)] {
+        &self.last_frame
+    }
+
+    #[inline]
+    pub fn duration(&self, label: &str) -> Option<Duration> {
+        self.last_frame
+            .iter()
+            .find(|(pass, _)| pass == label)
+            .map(|(_, duration)| *duration)
+    }
+
+    /// Number of frames that were not timed on the GPU because the readback
+    /// of an earlier frame had not completed yet, `last_frame` keeps the
+    /// timings of that earlier frame in the meantime
+    #[inline(always)]
+    pub const fn dropped_frames(&self) -> u64 {
+        self.dropped_frames
+    }
+
+    /// Collects the results of the last submitted frame without blocking,
+    /// this is called automatically when a new frame starts
+    pub fn poll(&mut self, render_context: &RenderContext) {
+        let Some(timestamps) = &mut self.timestamps else {
+            return;
+        };
+
+        let Some((_, receiver)) = &timestamps.pending else {
+            return;
+        };
+
+        let _ = unsafe { render_context.device() }.poll(wgpu::PollType::Poll);
+
+        match receiver.try_recv() {
+            Ok(Ok(())) => {}
+            Ok(Err(_)) | Err(mpsc::TryRecvError::Disconnected) => {
+                timestamps.pending = None;
+                return;
+            }
+            Err(mpsc::TryRecvError::Empty) => return,
+        }
+
+        let Some((labels, _)) = timestamps.pending.take() else {
+            return;
+        };
+
+        let size = labels.len() as u64 * 2 * wgpu::QUERY_SIZE as u64;
+
+        {
+            let mapped = timestamps.readback_buffer.slice(..size).get_mapped_range();
+            let ticks: &[u64] = bytemuck::cast_slice(&mapped);
+
+            self.last_frame = labels
+                .into_iter()
+                .zip(ticks.chunks_exact(2))
+                .map(|(label, ticks)| {
+                    let nanos = ticks[1].saturating_sub(ticks[0]) as f64 * timestamps.period as f64;
+                    (label, Duration::from_nanos(nanos as u64))
+                })
+                .collect();
+        }
+
+        timestamps.readback_buffer.unmap();
+    }
+
+    pub(crate) fn begin_frame(&mut self, render_context: &RenderContext) {
+        self.poll(render_context);
+        self.passes.clear();
+    }
+
+    /// Returns the query set and the first of the two query indices
+    /// reserved for this pass, if it is timed on the GPU
+    pub(crate) fn begin_pass(&mut self, label: &str) -> Option<(&wgpu::QuerySet, u32)> {
+        let index = self.passes.len() as u32;
+
+        match &self.timestamps {
+            Some(timestamps) if index < timestamps.max_passes => {
+                self.passes
+                    .push((label.to_owned(), Instant::now()..Instant::now()));
+                Some((&timestamps.query_set, index * 2))
+            }
+            Some(_) => None,
+            None => {
+                self.passes
+                    .push((label.to_owned(), Instant::now()..Instant::now()));
+                None
+            }
+        }
+    }
+
+    /// Records the end of the last pass when dropped, if passes are timed
+    /// by encode time
+    pub(crate) fn cpu_timer(&mut self) -> Option<PassTimer<'_>> {
+        if self.is_gpu() || self.passes.is_empty() {
+            return None;
+        }
+
+        Some(PassTimer {
+            index: self.passes.len() - 1,
+            profiler: self,
+        })
+    }
+
+    pub(crate) fn resolve(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        let count = self.passes.len() as u32 * 2;
+
+        match &self.timestamps {
+            Some(timestamps) if count > 0 && timestamps.pending.is_none() => {
+                encoder.resolve_query_set(
+                    &timestamps.query_set,
+                    0..count,
+                    &timestamps.resolve_buffer,
+                    0,
+                );
+                encoder.copy_buffer_to_buffer(
+                    &timestamps.resolve_buffer,
+                    0,
+                    &timestamps.readback_buffer,
+                    0,
+                    count as u64 * wgpu::QUERY_SIZE as u64,
+                );
+            }
+            Some(_) if count > 0 => self.dropped_frames += 1,
+            Some(_) => {}
+            None => self.resolve_cpu(),
+        }
+    }
+
+    /// Durations from the start of each pass to its end
+    fn resolve_cpu(&mut self) {
+        self.last_frame = self
+            .passes
+            .iter()
+            .map(|(label, time)| (label.clone(), time.end - time.start))
+            .collect();
+    }
+
+    pub(crate) fn after_submit(&mut self) {
+        let Some(timestamps) = &mut self.timestamps else {
+            return;
+        };
+
+        if self.passes.is_empty() || timestamps.pending.is_some() {
+            return;
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        timestamps
+            .readback_buffer
+            .slice(..self.passes.len() as u64 * 2 * wgpu::QUERY_SIZE as u64)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                let _ = sender.send(result);
+            });
+
+        let labels = self.passes.drain(..).map(|(label, _)| label).collect();
+        timestamps.pending = Some((labels, receiver));
+    }
+}
+
+/// Held by a pass timed by encode time, ending it when the pass is dropped
+#[derive(Debug)]
+pub(crate) struct PassTimer<'r> {
+    profiler: &'r mut Profiler,
+    index: usize,
+}
+
+impl Drop for PassTimer<'_> {
+    fn drop(&mut self) {
+        self.profiler.passes[self.index].1.end = Instant::now();
+    }
+}
+
+#[cfg(test)]
+mod profiler_tests {
+    use std::{thread, time::Duration};
+
+    use crate::prelude::*;
+
+    #[test]
+    fn test_cpu_fallback() {
+        let mut profiler = Profiler::new_fallback();
+        assert!(!profiler.is_gpu());
+
+        assert!(profiler.begin_pass("shadows").is_none());
+        {
+            let _timer = profiler.cpu_timer();
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        // time between passes is not part of either
+        thread::sleep(Duration::from_millis(50));
+
+        assert!(profiler.begin_pass("lighting").is_none());
+        drop(profiler.cpu_timer());
+
+        profiler.resolve_cpu();
+
+        let labels = profiler
+            .last_frame()
+            .iter()
+            .map(|(label, _)| label.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(labels, ["shadows", "lighting"]);
+
+        let shadows = profiler.duration("shadows").unwrap();
+        assert!(shadows >= Duration::from_millis(5));
+        assert!(shadows < Duration::from_millis(50));
+        assert!(profiler.duration("lighting").unwrap() < Duration::from_millis(50));
+        assert!(profiler.duration("sky").is_none());
+        assert_eq!(profiler.dropped_frames(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_cpu_fallback_encoder() {
+        let render_context = RenderContext::new(RenderContextConfig::default()).await;
+        let mut profiler = Profiler::new_fallback();
+
+        let mut encoder = render_context
+            .command_encoder()
+            .with_profiler(&mut profiler);
+        encoder.set_pass_label("culling");
+        drop(encoder.compute_pass());
+        thread::sleep(Duration::from_millis(50));
+        encoder.submit();
+
+        assert_eq!(profiler.last_frame().len(), 1);
+        assert!(profiler.duration("culling").unwrap() < Duration::from_millis(50));
+    }
+}
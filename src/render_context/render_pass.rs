@@ -1,4 +1,4 @@
-use crate::prelude::*;
+use crate::{prelude::*, render_context::profiler::PassTimer};
 
 pub mod render_pass_mut;
 
@@ -24,6 +24,7 @@ pub struct RenderPass<
     pub(crate) __layout: PhantomData<Layout>,
     pub(crate) __shader_attached: PhantomData<Shader>,
     pub(crate) instance: Instance,
+    pub(crate) timer: Option<PassTimer<'r>>,
 }
 
 impl<'r, L, S, const SA: bool, I: Copy> RenderPass<'r, L, S, SA, I> {
@@ -44,6 +45,7 @@ impl<'r, L, S, const SA: bool, I: Copy> RenderPass<'r, L, S, SA, I> {
             __layout: PhantomData,
             __shader_attached: PhantomData,
             instance: self.instance,
+            timer: self.timer,
         }
     }
 
@@ -63,6 +65,7 @@ impl<'r, L, S, const SA: bool, I: Copy> RenderPass<'r, L, S, SA, I> {
             __layout: PhantomData,
             __shader_attached: PhantomData,
             instance: Void,
+            timer: self.timer,
         }
     }
 
@@ -78,6 +81,7 @@ impl<'r, L, S, const SA: bool, I: Copy> RenderPass<'r, L, S, SA, I> {
             __layout: PhantomData,
             __shader_attached: PhantomData,
             instance: NoInstance,
+            timer: self.timer,
         }
     }
 
@@ -93,6 +97,7 @@ impl<'r, L, S, const SA: bool, I: Copy> RenderPass<'r, L, S, SA, I> {
             __layout: PhantomData,
             __shader_attached: PhantomData,
             instance: Void,
+            timer: self.timer,
         }
     }
 
@@ -129,7 +134,7 @@ impl<'r, L: Layout, S, const SA: bool, I: Copy> RenderPass<'r, L, S, SA, I> {
         shader: &NS,
         settings: &NS::Settings,
     ) -> RenderPass<'r, L, NS, true, I> {
-        unsafe { self.inner() }.set_pipeline(shader.get_pipeline());
+        unsafe { self.inner() }.set_pipeline(shader.get_pipeline_with(settings));
         NS::apply_settings(unsafe { self.inner() }, settings);
 
         unsafe { self.coerce() }
@@ -143,8 +148,10 @@ impl<'r, L: Layout, S, const SA: bool, I: Copy> RenderPass<'r, L, S, SA, I> {
     where
         NS::Settings: Default,
     {
-        unsafe { self.inner() }.set_pipeline(shader.get_pipeline());
-        NS::apply_settings(unsafe { self.inner() }, &default());
+        let settings = &default();
+
+        unsafe { self.inner() }.set_pipeline(shader.get_pipeline_with(settings));
+        NS::apply_settings(unsafe { self.inner() }, settings);
 
         unsafe { self.coerce() }
     }
@@ -163,9 +170,17 @@ impl<'r, L: Layout, S, const SA: bool, I: Copy> RenderPass<'r, L, S, SA, I> {
             __layout: PhantomData,
             __shader_attached: PhantomData,
             instance: Instanced { size },
+            timer: self.timer,
         }
     }
 
+    /// Sets the bind group at `index` again with the given dynamic offsets,
+    /// one for every dynamic entry of the bind in binding order
+    #[inline]
+    pub fn set_dynamic_offsets<B: Bind>(&mut self, index: u32, bind: &B, offsets: &[u32]) {
+        unsafe { self.inner() }.set_bind_group(index, bind.bind_group(), offsets);
+    }
+
     pub fn draw_screen_quad(&mut self)
     where
         L::VertexLayout: VertexRequirements<Requirements = ()>,
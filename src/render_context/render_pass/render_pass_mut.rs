@@ -116,7 +116,7 @@ impl<'m, 'r, L: Layout, I: Copy> RenderPassMut<'m, 'r, L, Void, false, I> {
         shader: &NS,
         settings: &NS::Settings,
     ) -> RenderPassMut<'m, 'r, L, NS, true, I> {
-        unsafe { self.inner() }.set_pipeline(shader.get_pipeline());
+        unsafe { self.inner() }.set_pipeline(shader.get_pipeline_with(settings));
         NS::apply_settings(unsafe { self.inner() }, settings);
 
         RenderPassMut {
@@ -135,8 +135,10 @@ impl<'m, 'r, L: Layout, I: Copy> RenderPassMut<'m, 'r, L, Void, false, I> {
     where
         NS::Settings: Default,
     {
-        unsafe { self.inner() }.set_pipeline(shader.get_pipeline());
-        NS::apply_settings(unsafe { self.inner() }, &default());
+        let settings = &default();
+
+        unsafe { self.inner() }.set_pipeline(shader.get_pipeline_with(settings));
+        NS::apply_settings(unsafe { self.inner() }, settings);
 
         RenderPassMut {
             render_pass: self.render_pass,
@@ -155,6 +157,14 @@ impl<'m, 'r, L: Layout, I: Copy> RenderPassMut<'m, 'r, L, Void, false, I> {
 }
 
 impl<'m, 'r, L: Layout, S: Shader, const SA: bool, I: Copy> RenderPassMut<'m, 'r, L, S, SA, I> {
+    /// Sets the bind group at `index` again with the given dynamic offsets,
+    /// one for every dynamic entry of the bind in binding order
+    #[inline]
+    pub fn set_dynamic_offsets<B: Bind>(mut self, index: u32, bind: &B, offsets: &[u32]) -> Self {
+        unsafe { self.inner() }.set_bind_group(index, bind.bind_group(), offsets);
+        self
+    }
+
     pub fn apply_settings(
         mut self,
         settings: &S::Settings,
@@ -0,0 +1,292 @@
+use std::sync::mpsc;
+
+use crate::prelude::*;
+
+#[derive(Debug, Clone)]
+pub struct RenderTargetConfig<'a> {
+    pub label: Option<&'a str>,
+    pub format: wgpu::TextureFormat,
+    pub usages: Option<wgpu::TextureUsages>,
+    pub clear: Option<wgpu::Color>,
+}
+
+impl Default for RenderTargetConfig<'_> {
+    fn default() -> Self {
+        Self {
+            label: None,
+            format: wgpu::TextureFormat::Bgra8UnormSrgb,
+            usages: None,
+            clear: None,
+        }
+    }
+}
+
+/// An offscreen colour target with its own depth texture, used in
+/// place of a `Window` when rendering without a surface
+#[derive(Debug, Clone)]
+pub struct RenderTarget {
+    pub texture: RawTexture<Texture2D>,
+    pub depth_texture: Texture<Texture2D>,
+
+    pub clear: Option<wgpu::Color>,
+}
+
+impl RenderTarget {
+    pub fn new(
+        render_context: &RenderContext,
+        width: u32,
+        height: u32,
+        config: &RenderTargetConfig,
+    ) -> Self {
+        let texture = RawTexture::new(
+            render_context,
+            wgpu::Extent3d {
+                width: width.max(1),
+                height: height.max(1),
+                depth_or_array_layers: 1,
+            },
+            &TextureConfig {
+                label: config.label,
+                format: Some(config.format),
+                usages: Some(config.usages.unwrap_or(
+                    wgpu::TextureUsages::RENDER_ATTACHMENT
+                        | wgpu::TextureUsages::TEXTURE_BINDING
+                        | wgpu::TextureUsages::COPY_SRC,
+                )),
+                ..default()
+            },
+        );
+
+        let depth_texture = Texture::create_depth_texture_sized(render_context, width, height);
+
+        Self {
+            texture,
+            depth_texture,
+            clear: config.clear,
+        }
+    }
+
+    #[inline]
+    pub fn size(&self) -> (u32, u32) {
+        let size = unsafe { self.texture.inner() }.size();
+        (size.width, size.height)
+    }
+
+    #[inline]
+    pub fn format(&self) -> wgpu::TextureFormat {
+        unsafe { self.texture.inner() }.format()
+    }
+
+    pub fn resize(&mut self, render_context: &RenderContext, width: u32, height: u32) {
+        self.texture.resize(
+            render_context,
+            None,
+            wgpu::Extent3d {
+                width: width.max(1),
+                height: height.max(1),
+                depth_or_array_layers: 1,
+            },
+        );
+
+        self.depth_texture = Texture::create_depth_texture_sized(render_context, width, height);
+    }
+
+    /// Copies the colour texture into a staging buffer and blocks until it
+    /// can be read back, returning tightly packed rows of texels
+    ///
+    /// Any work previously submitted to the queue is finished first, so this
+    /// should be called after the `CommandEncoder` that drew into the target
+    /// has been submitted
+    pub fn read(&self, render_context: &RenderContext) -> Vec<u8> {
+        let device = unsafe { render_context.device() };
+        let texture = unsafe { self.texture.inner() };
+
+        let (width, height) = self.size();
+        let bytes_per_texel = self
+            .format()
+            .block_copy_size(None)
+            .expect("render target format cannot be copied");
+
+        let unpadded_bytes_per_row = width * bytes_per_texel;
+        let padded_bytes_per_row =
+            unpadded_bytes_per_row.next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+
+        let staging = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Render Target Readback"),
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            size: padded_bytes_per_row as u64 * height as u64,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = render_context.command_encoder();
+
+        encoder.encoder.copy_texture_to_buffer(
+            wgpu::TexelCopyTextureInfo {
+                texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::TexelCopyBufferInfo {
+                buffer: &staging,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            texture.size(),
+        );
+
+        encoder.submit();
+
+        let (sender, receiver) = mpsc::channel();
+        staging
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                let _ = sender.send(result);
+            });
+
+        device
+            .poll(wgpu::PollType::wait_indefinitely())
+            .expect("failed to poll device");
+        receiver
+            .recv()
+            .expect("map callback was dropped")
+            .expect("failed to map readback buffer");
+
+        let mut data = Vec::with_capacity(unpadded_bytes_per_row as usize * height as usize);
+
+        {
+            let mapped = staging.slice(..).get_mapped_range();
+
+            for row in mapped.chunks_exact(padded_bytes_per_row as usize) {
+                data.extend_from_slice(&row[..unpadded_bytes_per_row as usize]);
+            }
+        }
+
+        staging.unmap();
+
+        data
+    }
+}
+
+#[cfg(test)]
+mod render_target_tests {
+    use crate::prelude::*;
+
+    #[tokio::test]
+    async fn test_clear_readback() {
+        let render_context = RenderContext::new(RenderContextConfig::default()).await;
+
+        let target = RenderTarget::new(
+            &render_context,
+            4,
+            4,
+            &RenderTargetConfig {
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                clear: Some(wgpu::Color::RED),
+                ..default()
+            },
+        );
+
+        let mut encoder = render_context.command_encoder();
+        drop(encoder.render_target_pass(&target, None, true));
+        encoder.submit();
+
+        let data = target.read(&render_context);
+
+        assert_eq!(data.len(), 4 * 4 * 4);
+        assert!(data.chunks_exact(4).all(|texel| texel == [255, 0, 0, 255]));
+    }
+
+    #[tokio::test]
+    async fn test_multiple_targets() {
+        let render_context = RenderContext::new(RenderContextConfig::default()).await;
+
+        let config = RenderTargetConfig {
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            ..default()
+        };
+
+        let color = RenderTarget::new(&render_context, 4, 4, &config);
+        let ids = RenderTarget::new(&render_context, 4, 4, &config);
+
+        let layout = RawLayout::<Void>::new(
+            &render_context,
+            LayoutConfig {
+                targets: Some(&[
+                    Some(wgpu::TextureFormat::Rgba8Unorm.into()),
+                    Some(wgpu::ColorTargetState {
+                        format: wgpu::TextureFormat::Rgba8Unorm,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::RED,
+                    }),
+                ]),
+                ..default()
+            },
+        );
+
+        let module = render_context.create_shader_module(
+            None,
+            wgpu::ShaderSource::Wgsl(
+                "
+                struct Targets {
+                    @location(0) color: vec4<f32>,
+                    @location(1) id: vec4<f32>,
+                };
+
+                @vertex
+                fn vs(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+                    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+                    return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+                }
+
+                @fragment
+                fn fs() -> Targets {
+                    return Targets(vec4<f32>(0.0, 0.0, 1.0, 1.0), vec4<f32>(1.0, 0.0, 1.0, 0.0));
+                }
+                "
+                .into(),
+            ),
+        );
+
+        let pipeline = layout
+            .create_pipeline_checked(
+                &render_context,
+                &module,
+                ShaderConfig {
+                    depth_stencil: Some(None),
+                    ..default()
+                },
+            )
+            .await;
+
+        let pipeline = pipeline.unwrap();
+
+        let mut encoder = render_context.command_encoder();
+        let mut render_pass = encoder.render_pass_targets(
+            &[unsafe { color.texture.view() }, unsafe {
+                ids.texture.view()
+            }],
+            Some(wgpu::LoadOp::Clear(wgpu::Color::GREEN)),
+            None,
+        );
+        let inner = unsafe { render_pass.inner() };
+        inner.set_pipeline(&pipeline);
+        // a single triangle covering the whole target
+        inner.draw(0..3, 0..1);
+        drop(render_pass);
+        encoder.submit();
+
+        // every channel of the first target is written
+        let data = color.read(&render_context);
+        assert!(data.chunks_exact(4).all(|texel| texel == [0, 0, 255, 255]));
+
+        // only red is written to the second, the rest keeps the clear color
+        let data = ids.read(&render_context);
+        assert!(data
+            .chunks_exact(4)
+            .all(|texel| texel == [255, 255, 0, 255]));
+    }
+}
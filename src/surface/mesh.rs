@@ -71,19 +71,22 @@ macro_rules! simple_mesh_impl {
                 }
             }
 
-            pub fn new_uint16(render_context: &RenderContext, $($buffer: &[$As],)* indices: &[u16]) -> Self {
+            #[allow(clippy::too_many_arguments)]
+            pub fn new_uint16(render_context: &RenderContext, label: Option<&str>, $($buffer: &[$As],)* indices: &[u16]) -> Self {
                 let device = unsafe { render_context.device() };
 
                 $(
                     let $buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                        label: None,
+                        label: label
+                            .map(|label| format!("{label} {}", stringify!($buffer)))
+                            .as_deref(),
                         contents: bytemuck::cast_slice($buffer),
                         usage: wgpu::BufferUsages::VERTEX,
                     });
                 )*
 
                 let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                    label: None,
+                    label: label.map(|label| format!("{label} index_buffer")).as_deref(),
                     contents: bytemuck::cast_slice(indices),
                     usage: wgpu::BufferUsages::INDEX,
                 });
@@ -91,19 +94,22 @@ macro_rules! simple_mesh_impl {
                 unsafe { Self::from_raw($($buffer,)* index_buffer, indices.len() as u32) }
             }
 
-            pub fn new_uint32(render_context: &RenderContext, $($buffer: &[$As],)* indices: &[u32]) -> Self {
+            #[allow(clippy::too_many_arguments)]
+            pub fn new_uint32(render_context: &RenderContext, label: Option<&str>, $($buffer: &[$As],)* indices: &[u32]) -> Self {
                 let device = unsafe { render_context.device() };
 
                 $(
                     let $buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                        label: None,
+                        label: label
+                            .map(|label| format!("{label} {}", stringify!($buffer)))
+                            .as_deref(),
                         contents: bytemuck::cast_slice($buffer),
                         usage: wgpu::BufferUsages::VERTEX,
                     });
                 )*
 
                 let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                    label: None,
+                    label: label.map(|label| format!("{label} index_buffer")).as_deref(),
                     contents: bytemuck::cast_slice(indices),
                     usage: wgpu::BufferUsages::INDEX,
                 });
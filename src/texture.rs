@@ -19,6 +19,14 @@ pub mod texture_dimension {
     pub struct Texture2D;
     #[derive(Default, Debug, Clone, Copy)]
     pub struct Texture3D;
+    #[derive(Default, Debug, Clone, Copy)]
+    pub struct Texture2DArray;
+    /// A 2D texture with 6 array layers, viewed as the faces of a cube
+    #[derive(Default, Debug, Clone, Copy)]
+    pub struct TextureCube;
+    /// A 2D texture with a multiple of 6 array layers, viewed as cubes
+    #[derive(Default, Debug, Clone, Copy)]
+    pub struct TextureCubeArray;
 
     impl TextureDimension for Texture1D {
         const DIMENSION: wgpu::TextureDimension = wgpu::TextureDimension::D1;
@@ -32,9 +40,23 @@ pub mod texture_dimension {
         const DIMENSION: wgpu::TextureDimension = wgpu::TextureDimension::D3;
         const VIEW_DIMENSION: wgpu::TextureViewDimension = wgpu::TextureViewDimension::D3;
     }
+    impl TextureDimension for Texture2DArray {
+        const DIMENSION: wgpu::TextureDimension = wgpu::TextureDimension::D2;
+        const VIEW_DIMENSION: wgpu::TextureViewDimension = wgpu::TextureViewDimension::D2Array;
+    }
+    impl TextureDimension for TextureCube {
+        const DIMENSION: wgpu::TextureDimension = wgpu::TextureDimension::D2;
+        const VIEW_DIMENSION: wgpu::TextureViewDimension = wgpu::TextureViewDimension::Cube;
+    }
+    impl TextureDimension for TextureCubeArray {
+        const DIMENSION: wgpu::TextureDimension = wgpu::TextureDimension::D2;
+        const VIEW_DIMENSION: wgpu::TextureViewDimension = wgpu::TextureViewDimension::CubeArray;
+    }
 }
-use texture_dimension::TextureDimension;
-pub use texture_dimension::{Texture1D, Texture2D, Texture3D};
+pub use texture_dimension::{
+    Texture1D, Texture2D, Texture2DArray, Texture3D, TextureCube, TextureCubeArray,
+    TextureDimension,
+};
 
 impl<DIMENSION: TextureDimension> Texture<DIMENSION> {
     #[inline(always)]
@@ -45,14 +67,28 @@ impl<DIMENSION: TextureDimension> Texture<DIMENSION> {
 
 impl Texture<Texture2D> {
     pub const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+    /// Sample type to bind a depth texture with, for shadow sampling
+    pub const DEPTH_SAMPLE_TYPE: wgpu::TextureSampleType = wgpu::TextureSampleType::Depth;
+    /// Binding type of the comparison sampler created with a depth texture
+    pub const DEPTH_SAMPLER_BINDING_TYPE: wgpu::SamplerBindingType =
+        wgpu::SamplerBindingType::Comparison;
 
+    #[inline(always)]
     pub fn create_depth_texture(
         render_context: &RenderContext,
         config: &wgpu::SurfaceConfiguration,
+    ) -> Self {
+        Self::create_depth_texture_sized(render_context, config.width, config.height)
+    }
+
+    pub fn create_depth_texture_sized(
+        render_context: &RenderContext,
+        width: u32,
+        height: u32,
     ) -> Self {
         let size = wgpu::Extent3d {
-            width: config.width.max(1),
-            height: config.height.max(1),
+            width: width.max(1),
+            height: height.max(1),
             depth_or_array_layers: 1,
         };
         let cfg = TextureConfig::<Texture2D> {
@@ -74,6 +110,7 @@ impl Texture<Texture2D> {
             compare: Some(wgpu::CompareFunction::LessEqual),
             lod_min_clamp: 0.0,
             lod_max_clamp: 100.0,
+            label: Some("Depth Sampler"),
             ..Default::default()
         };
 
@@ -89,6 +126,7 @@ pub struct RawTexture<DIMENSION: TextureDimension = Texture2D> {
     texture: wgpu::Texture,
     view: RawTextureView<DIMENSION>,
     view_formats: Box<[wgpu::TextureFormat]>,
+    label: Option<Box<str>>,
     __dimension: PhantomData<DIMENSION>,
 }
 
@@ -146,6 +184,7 @@ impl<DIMENSION: TextureDimension> RawTexture<DIMENSION> {
             texture,
             view,
             view_formats,
+            label: cfg.label.map(Into::into),
             __dimension: PhantomData,
         }
     }
@@ -191,12 +230,19 @@ impl<DIMENSION: TextureDimension> RawTexture<DIMENSION> {
         );
     }
 
+    /// Recreates the texture with a new size, keeping the label it was
+    /// created with unless a new one is given
     pub fn resize(
         &mut self,
         render_context: &RenderContext,
         label: Option<&str>,
         size: wgpu::Extent3d,
     ) {
+        if let Some(label) = label {
+            self.label = Some(label.into());
+        }
+        let label = self.label.as_deref();
+
         self.texture = unsafe {
             render_context
                 .device()
@@ -224,6 +270,11 @@ impl<DIMENSION: TextureDimension> RawTexture<DIMENSION> {
         self.view = unsafe { RawTextureView::new(texture_view) };
     }
 
+    #[inline(always)]
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
     /// # Safety
     /// This function is unsafe because it returns the inner `wgpu::Texture`
     pub const unsafe fn inner(&self) -> &wgpu::Texture {